@@ -17,7 +17,7 @@ benchmarks! {
 
     create_collection {
         let caller: T::AccountId = whitelisted_caller();
-    }: create_collection(RawOrigin::Signed(caller.clone()), Vec::<u8>::default(), ClassData::default())
+    }: create_collection(RawOrigin::Signed(caller.clone()), Vec::<u8>::default(), ClassData::default(), None)
     verify {
         assert_eq!(
             last_event(),
@@ -27,7 +27,7 @@ benchmarks! {
 
     mint {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
     }: mint(RawOrigin::Signed(caller.clone()), Default::default(), Vec::<u8>::default(), TokenData::default())
     verify {
         assert_eq!(
@@ -39,7 +39,7 @@ benchmarks! {
     // TODO: use non-default balance to appreciate
     appreciate {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
     }: appreciate(RawOrigin::Signed(caller.clone()), Default::default(), Default::default(), Default::default())
     verify {
@@ -51,7 +51,7 @@ benchmarks! {
 
     toggle_display {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
     }: toggle_display(RawOrigin::Signed(caller.clone()), Default::default(), Default::default(), Default::default())
     verify {
@@ -63,7 +63,7 @@ benchmarks! {
 
     transfer {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
     }: transfer(RawOrigin::Signed(caller.clone()), Default::default(), Default::default(), Default::default())
     verify {
@@ -76,28 +76,30 @@ benchmarks! {
     // TODO: where is AccountId in event coming from
     create_offer {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
         let offerer: T::AccountId = account("offerer", 0, 0);
-    }: create_offer(RawOrigin::Signed(offerer.clone()), Default::default(), Default::default(), Default::default())
+    }: create_offer(RawOrigin::Signed(offerer.clone()), Default::default(), Default::default(), Default::default(), Default::default(), None)
     verify {
         assert_eq!(
           last_event(),
-          crate::mock::Event::chiba(crate::RawEvent::OfferCreated(0, 0, 0, 15504002658165612567)),
+          crate::mock::Event::chiba(crate::RawEvent::OfferCreated(0, 0, 0, 0, 15504002658165612567)),
         );
     }
 
     // TODO: where are AccountIds in event coming from
     accept_offer {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
         let offerer: T::AccountId = account("offerer", 0, 0);
         Chiba::<T>::create_offer(
             <T as frame_system::Config>::Origin::from(RawOrigin::Signed(offerer.clone())),
             Default::default(),
             Default::default(),
-            Default::default()
+            Default::default(),
+            Default::default(),
+            None
         )?;
     }: accept_offer(RawOrigin::Signed(caller.clone()), Default::default(), Default::default(), offerer)
     verify {
@@ -111,14 +113,16 @@ benchmarks! {
     // TODO: where are AccountIds in event coming from
     cancel_offer {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
         let offerer: T::AccountId = account("offerer", 0, 0);
         Chiba::<T>::create_offer(
             <T as frame_system::Config>::Origin::from(RawOrigin::Signed(offerer.clone())),
             Default::default(),
             Default::default(),
-            Default::default()
+            Default::default(),
+            Default::default(),
+            None
         )?;
     }: cancel_offer(RawOrigin::Signed(offerer.clone()), Default::default(), Default::default())
     verify {
@@ -130,7 +134,7 @@ benchmarks! {
 
     report {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
         let reporter: T::AccountId = account("reporter", 0, 0);
     }: report(RawOrigin::Signed(reporter.clone()), Default::default(), Default::default(), ReportReason::None)
@@ -143,7 +147,7 @@ benchmarks! {
 
     accept_report {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
         let reporter: T::AccountId = account("reporter", 0, 0);
         Chiba::<T>::report(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(reporter.clone())), Default::default(), Default::default(), ReportReason::None)?;
@@ -159,7 +163,7 @@ benchmarks! {
 
     clear_report {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
         let reporter: T::AccountId = account("reporter", 0, 0);
         Chiba::<T>::report(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(reporter.clone())), Default::default(), Default::default(), ReportReason::None)?;
@@ -173,9 +177,21 @@ benchmarks! {
         );
     }
 
+    list_token {
+        let caller: T::AccountId = whitelisted_caller();
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
+        Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
+    }: list_token(RawOrigin::Signed(caller.clone()), Default::default(), Default::default(), Default::default(), Default::default())
+    verify {
+        assert_eq!(
+          last_event(),
+          crate::mock::Event::chiba(crate::RawEvent::TokenListed(0, 0, 0, 0)),
+        );
+    }
+
     burn {
         let caller: T::AccountId = whitelisted_caller();
-        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default())?;
+        Chiba::<T>::create_collection(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Vec::<u8>::default(), ClassData::default(), None)?;
         Chiba::<T>::mint(<T as frame_system::Config>::Origin::from(RawOrigin::Signed(caller.clone())), Default::default(), Vec::<u8>::default(), TokenData::default())?;
     }: burn(RawOrigin::Signed(caller.clone()), Default::default(), Default::default())
     verify {
@@ -276,6 +292,13 @@ mod tests {
         });
     }
 
+    #[test]
+    fn list_token() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_list_token::<Test>());
+        });
+    }
+
     #[test]
     fn burn() {
         new_test_ext().execute_with(|| {
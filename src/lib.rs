@@ -9,22 +9,81 @@ mod tests;
 #[cfg(test)]
 mod benchmarking;
 
-use codec::{Decode, Encode};
-use frame_support::traits::{BalanceStatus, Currency, ExistenceRequirement, Get};
+pub mod runtime_api;
+
+use codec::{Decode, Encode, FullCodec};
+use frame_support::traits::{
+    BalanceStatus, Currency, ExistenceRequirement, Get, OnUnbalanced, WithdrawReasons,
+};
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage, ensure, fail, traits::ReservableCurrency,
+    decl_error, decl_event, decl_module, decl_storage, ensure, fail, storage::IterableStorageDoubleMap,
+    traits::ReservableCurrency, transactional, weights::Weight,
 };
 use frame_system::{ensure_root, ensure_signed};
-use sp_runtime::{DispatchResult, RuntimeDebug};
+use sp_runtime::{
+    traits::{AccountIdConversion, Hash as HashT, One, SaturatedConversion, Saturating, Zero},
+    DispatchResult, ModuleId, Perbill, RuntimeDebug,
+};
 use sp_std::prelude::*;
 
 use orml_nft::{self as nft};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
 type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+type CurrencyIdOf<T> = <<T as Config>::MultiCurrency as MultiCurrency<
+    <T as frame_system::Config>::AccountId,
+>>::CurrencyId;
+type OfferOf<T> = Offer<BalanceOf<T>, <T as frame_system::Config>::BlockNumber, CurrencyIdOf<T>>;
+type ListingOf<T> = Listing<BalanceOf<T>, CurrencyIdOf<T>>;
+type SaleSettingsOf<T> = SaleSettings<BalanceOf<T>, CurrencyIdOf<T>>;
+type CollectionOfferOf<T> = CollectionOffer<BalanceOf<T>, CurrencyIdOf<T>>;
+type BundleOf<T> = Bundle<
+    <T as frame_system::Config>::AccountId,
+    <T as nft::Config>::ClassId,
+    <T as nft::Config>::TokenId,
+    BalanceOf<T>,
+    CurrencyIdOf<T>,
+>;
+type EnglishAuctionOf<T> = EnglishAuction<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+type DutchAuctionOf<T> = DutchAuction<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+type SealedAuctionOf<T> = SealedAuction<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+type SealedBidOf<T> = SealedBid<<T as frame_system::Config>::Hash, BalanceOf<T>>;
+type InstallmentPlanOf<T> = InstallmentPlan<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+type RentalTermsOf<T> = RentalTerms<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+type MintConfigOf<T> = MintConfig<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+>;
+type EscrowOf<T> = Escrow<
+    <T as frame_system::Config>::AccountId,
+    BalanceOf<T>,
+    <T as frame_system::Config>::BlockNumber,
+    CurrencyIdOf<T>,
+>;
 
 #[derive(Encode, Decode, Clone, Debug, PartialEq)]
 pub struct ExtendedInfo {
@@ -43,6 +102,177 @@ pub enum ReportReason {
     Reported,
 }
 
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum Party {
+    Buyer,
+    Owner,
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Listing<Balance, CurrencyId> {
+    pub price: Balance,
+    pub currency_id: CurrencyId,
+}
+
+/// Owner-defined thresholds for offers on a token, both denominated in `currency_id`.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct SaleSettings<Balance, CurrencyId> {
+    pub currency_id: CurrencyId,
+    pub min_offer: Option<Balance>,
+    pub auto_accept: Option<Balance>,
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Offer<Balance, BlockNumber, CurrencyId> {
+    /// The buyer's current price, which is what stays reserved.
+    pub amount: Balance,
+    pub currency_id: CurrencyId,
+    /// The offer can no longer be accepted from this block on.
+    pub expires: Option<BlockNumber>,
+    /// The owner's latest counter-price, if any.
+    pub counter: Option<Balance>,
+    /// Who is expected to respond next.
+    pub turn: Party,
+    /// Every price put forward so far, oldest first.
+    pub rounds: Vec<(Party, Balance)>,
+}
+
+/// A standing offer to buy up to `quantity` tokens of a collection at `price` each.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct CollectionOffer<Balance, CurrencyId> {
+    pub price: Balance,
+    pub currency_id: CurrencyId,
+    pub quantity: u32,
+}
+
+pub type BundleId = u32;
+
+/// Several tokens sold together for one price. For a listing `account` is the seller, for an
+/// offer it is the buyer.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Bundle<AccountId, ClassId, TokenId, Balance, CurrencyId> {
+    pub account: AccountId,
+    pub items: Vec<(ClassId, TokenId)>,
+    pub price: Balance,
+    pub currency_id: CurrencyId,
+}
+
+/// An ascending-price auction. The token stays frozen until the auction is settled.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct EnglishAuction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    pub reserve_price: Balance,
+    pub min_increment: Balance,
+    pub end: BlockNumber,
+    pub high_bid: Option<(AccountId, Balance)>,
+}
+
+/// A descending-price auction. The price falls linearly from `start_price` to `floor_price`
+/// over `decay_period` blocks and then holds at the floor until `end`.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct DutchAuction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    pub start_price: Balance,
+    pub floor_price: Balance,
+    pub start: BlockNumber,
+    pub decay_period: BlockNumber,
+    pub end: BlockNumber,
+}
+
+/// A sealed-bid auction. Bids are committed as `hash(bidder, amount, salt)` before `commit_end`
+/// and revealed before `reveal_end`, when the auction is settled.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct SealedAuction<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    pub reserve_price: Balance,
+    pub commit_end: BlockNumber,
+    pub reveal_end: BlockNumber,
+    pub bid_count: u32,
+    pub best: Option<(AccountId, Balance)>,
+}
+
+/// The block-indexed queues drained in `on_initialize`.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum SettlementQueue {
+    Auctions,
+    DutchAuctions,
+    SealedAuctions,
+    Installments,
+    Rentals,
+    MintPhases,
+    Escrows,
+}
+
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct SealedBid<Hash, Balance> {
+    pub commitment: Hash,
+    pub deposit: Balance,
+    pub revealed: Option<Balance>,
+}
+
+/// A completed sale whose payment is held in the escrow account until `release_at`. The
+/// buyer already holds the token, frozen, for the length of the window.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Escrow<AccountId, Balance, BlockNumber, CurrencyId> {
+    pub seller: AccountId,
+    pub buyer: AccountId,
+    pub price: Balance,
+    pub currency_id: CurrencyId,
+    pub release_at: BlockNumber,
+    pub disputed: bool,
+}
+
+/// A layaway sale paid in the native currency. Payments are reserved on the buyer and the
+/// token stays frozen with the seller until `paid` reaches `price`. `next_due` is `None` until
+/// the buyer starts the plan by paying the deposit.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct InstallmentPlan<AccountId, Balance, BlockNumber> {
+    pub seller: AccountId,
+    pub buyer: AccountId,
+    pub price: Balance,
+    pub deposit: Balance,
+    pub paid: Balance,
+    /// Installments left after the deposit.
+    pub installments: u32,
+    pub period: BlockNumber,
+    pub next_due: Option<BlockNumber>,
+    /// Share of the paid amount the seller keeps if the buyer misses a deadline.
+    pub forfeit: Perbill,
+}
+
+/// Terms under which an owner lends a token out, paid upfront in the native currency.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct RentalTerms<Balance, BlockNumber> {
+    pub price_per_block: Balance,
+    pub max_duration: BlockNumber,
+}
+
+/// A public sale of new tokens in a collection, paid in the native currency. Allowlisted
+/// accounts may mint from `allowlist_start`, everyone from `public_start`, until `end`.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct MintConfig<AccountId, Balance, BlockNumber> {
+    pub price: Balance,
+    pub beneficiary: AccountId,
+    pub allowlist_start: BlockNumber,
+    pub public_start: BlockNumber,
+    pub end: BlockNumber,
+    pub per_account_limit: u32,
+}
+
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum MintPhase {
+    Allowlist,
+    Public,
+    Closed,
+}
+
+/// Share of every sale paid to the creator of a collection or token.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Royalty<AccountId> {
+    pub beneficiary: AccountId,
+    pub rate: Perbill,
+}
+
 decl_error! {
     pub enum Error for Module<T: Config> {
         CollectionNotFound,
@@ -53,7 +283,76 @@ decl_error! {
         NotCollectionOwnerOrCurator,
         NotCurator,
         LowBalance,
-        TokenFrozen
+        TokenFrozen,
+        ListingNotFound,
+        CannotBuyOwnToken,
+        AuctionNotFound,
+        AuctionEnded,
+        InvalidAuctionEnd,
+        BidTooLow,
+        AuctionHasBids,
+        InvalidPriceRange,
+        NotInCommitPhase,
+        NotInRevealPhase,
+        BidAlreadyCommitted,
+        BidNotFound,
+        InvalidReveal,
+        TooManyBids,
+        NothingToSettle,
+        RoyaltyTooHigh,
+        FeeTooHigh,
+        InvalidExpiry,
+        ExpiredOffer,
+        OfferNotExpired,
+        OfferAlreadyExists,
+        InvalidQuantity,
+        NotYourTurn,
+        NoCounterOffer,
+        TooManyRounds,
+        CurrencyNotAccepted,
+        TooManyCurrencies,
+        InvalidBundle,
+        BundleNotFound,
+        AlreadyBundled,
+        TooManyOffers,
+        TooManyAccountOffers,
+        OfferBelowMinimum,
+        InvalidBuyers,
+        NotAllowedBuyer,
+        EscrowNotFound,
+        NotEscrowBuyer,
+        DisputeWindowClosed,
+        AlreadyDisputed,
+        NotDisputed,
+        EscrowTooSmall,
+        InvalidPlan,
+        PlanNotFound,
+        PlanAlreadyExists,
+        PlanAlreadyStarted,
+        PlanNotStarted,
+        NotPlanBuyer,
+        InstallmentOverdue,
+        RentalNotFound,
+        InvalidRentalDuration,
+        AlreadyRented,
+        NotTokenUser,
+        Soulbound,
+        InvalidSettings,
+        TransfersDisabled,
+        SalesPending,
+        BurningDisabled,
+        MetadataLocked,
+        OffersDisabled,
+        MaxSupplyReached,
+        MaxSupplyAlreadySet,
+        InvalidMaxSupply,
+        InvalidMintWindow,
+        MintNotOpen,
+        MintClosed,
+        NotOnAllowlist,
+        MintLimitReached,
+        TooManyAccounts,
+        InvalidProof
     }
 }
 
@@ -61,6 +360,40 @@ decl_error! {
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct ClassData {
     pub name: Vec<u8>,
+    /// Tokens can't be transferred, sold or swapped once minted. Fixed at creation.
+    pub soulbound: bool,
+    /// Holders may burn their own tokens, not just the collection owner or curator.
+    pub holder_burnable: bool,
+}
+
+/// Bitflags controlling what may be done with a collection's tokens. A set bit allows the
+/// operation. Owners can clear bits but never set them again.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct CollectionSettings(pub u8);
+
+impl CollectionSettings {
+    pub const TRANSFERABLE: u8 = 1 << 0;
+    pub const BURNABLE: u8 = 1 << 1;
+    pub const METADATA_MUTABLE: u8 = 1 << 2;
+    /// Checked whenever an offer is made, countered or accepted; withdrawing one is always allowed.
+    pub const OFFERS_ENABLED: u8 = 1 << 3;
+    pub const ALL: u8 = Self::TRANSFERABLE | Self::BURNABLE | Self::METADATA_MUTABLE | Self::OFFERS_ENABLED;
+
+    pub fn allows(&self, flag: u8) -> bool {
+        self.0 & flag == flag
+    }
+
+    /// Whether every operation allowed by `self` is also allowed by `other`.
+    pub fn is_stricter_than(&self, other: &Self) -> bool {
+        self.0 & !other.0 == 0
+    }
+}
+
+impl Default for CollectionSettings {
+    fn default() -> Self {
+        CollectionSettings(Self::ALL)
+    }
 }
 
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, Default)]
@@ -76,6 +409,57 @@ pub trait Config:
 {
     type Event: From<Event<Self>> + Into<<Self as frame_system::Config>::Event>;
     type Currency: ReservableCurrency<Self::AccountId>;
+    /// Bids placed this close to the end of an auction push the end back by the same amount.
+    type AuctionExtendPeriod: Get<Self::BlockNumber>;
+    /// Maximum number of sealed bids a single auction accepts.
+    type MaxSealedBids: Get<u32>;
+    /// Portion of a sealed-bid deposit that is slashed when the bid is never revealed.
+    type UnrevealedBidSlash: Get<Perbill>;
+    /// Upper bound for any collection or token royalty.
+    type MaxRoyalty: Get<Perbill>;
+    /// Marketplace fee charged on every sale until governance sets a different rate.
+    type MarketplaceFee: Get<Perbill>;
+    /// Upper bound for the marketplace fee governance can set.
+    type MaxMarketplaceFee: Get<Perbill>;
+    /// Receives the marketplace fee taken from each sale.
+    type FeeDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+    /// Maximum number of expired offers refunded in `on_initialize` each block.
+    type MaxOfferExpiriesPerBlock: Get<u32>;
+    /// Maximum number of entries settled from each queue in `on_initialize` each block. The
+    /// rest carry over to the following blocks.
+    type MaxSettlementsPerBlock: Get<u32>;
+    /// Paid out of an expired offer's reserve to whoever reaps it.
+    type OfferReapReward: Get<BalanceOf<Self>>;
+    /// Maximum number of prices recorded in a single offer negotiation.
+    type MaxNegotiationRounds: Get<u32>;
+    /// Currencies other than `Currency` that offers and listings can be priced in.
+    type MultiCurrency: MultiReservableCurrency<Self::AccountId, Balance = BalanceOf<Self>>;
+    /// The id under which `MultiCurrency` refers to `Currency`. Payments in this currency go
+    /// through `Currency` directly.
+    type NativeCurrencyId: Get<CurrencyIdOf<Self>>;
+    /// Receives marketplace fees paid in currencies other than the native one.
+    type FeeCollector: Get<Self::AccountId>;
+    /// Maximum number of currencies an owner can restrict a token to.
+    type MaxAcceptedCurrencies: Get<u32>;
+    /// Maximum number of tokens in a bundle listing or offer.
+    type MaxBundleSize: Get<u32>;
+    /// Maximum number of open offers on a single token. All of them are refunded whenever the
+    /// token changes hands, so this bounds the cost of a transfer.
+    type MaxOffersPerToken: Get<u32>;
+    /// Maximum number of open offers a single account may have. Bounds `cancel_all_offers`.
+    type MaxOffersPerAccount: Get<u32>;
+    /// Maximum number of accounts allowed to buy a privately listed token.
+    type MaxPrivateBuyers: Get<u32>;
+    /// Id used to derive the account that holds escrowed payments.
+    type EscrowModuleId: Get<ModuleId>;
+    /// Number of blocks an escrowed payment is held, during which the buyer may dispute.
+    type EscrowPeriod: Get<Self::BlockNumber>;
+    /// Maximum number of installments after the deposit in a layaway plan.
+    type MaxInstallments: Get<u32>;
+    /// Maximum number of accounts added to or removed from a mint allowlist in one call.
+    type MaxAllowlistBatch: Get<u32>;
+    /// Maximum number of hashes in a Merkle proof passed to `claim_mint`.
+    type MaxProofLength: Get<u32>;
 }
 
 decl_event!(
@@ -85,19 +469,90 @@ decl_event!(
         ClassId = <T as nft::Config>::ClassId,
         TokenId = <T as nft::Config>::TokenId,
         Balance = BalanceOf<T>,
+        BlockNumber = <T as frame_system::Config>::BlockNumber,
+        CurrencyId = CurrencyIdOf<T>,
     {
         CollectionCreated(ClassId),
         TokenMinted(ClassId, TokenId),
         TokenAppreciated(ClassId, TokenId, Balance),
         TokenDisplayToggled(ClassId, TokenId, bool),
         TokenTransferred(ClassId, TokenId, AccountId),
-        OfferCreated(ClassId, TokenId, Balance, AccountId),
+        OfferCreated(ClassId, TokenId, Balance, CurrencyId, AccountId),
         OfferAccepted(ClassId, TokenId, AccountId, AccountId),
         OfferCanceled(ClassId, TokenId, AccountId, AccountId),
         ReportReceived(ClassId, TokenId, ReportReason),
         ReportAccepted(ClassId, TokenId),
         ReportCleared(ClassId, TokenId),
         TokenBurned(ClassId, TokenId),
+        TokenListed(ClassId, TokenId, Balance, CurrencyId),
+        TokenListedPrivately(ClassId, TokenId, Balance, CurrencyId, Vec<AccountId>),
+        ListingEscrowSet(ClassId, TokenId, bool),
+        EscrowOpened(ClassId, TokenId, BlockNumber),
+        EscrowDisputed(ClassId, TokenId, AccountId),
+        EscrowReleased(ClassId, TokenId, AccountId, Balance),
+        SaleReversed(ClassId, TokenId, AccountId, Balance),
+        InstallmentPlanCreated(ClassId, TokenId, AccountId, Balance),
+        InstallmentPlanStarted(ClassId, TokenId, AccountId, BlockNumber),
+        InstallmentPaid(ClassId, TokenId, AccountId, Balance),
+        InstallmentPlanCompleted(ClassId, TokenId, AccountId, Balance),
+        InstallmentPlanDefaulted(ClassId, TokenId, AccountId, Balance),
+        InstallmentPlanCanceled(ClassId, TokenId),
+        RentalTermsSet(ClassId, TokenId, Balance, BlockNumber),
+        RentalTermsRemoved(ClassId, TokenId),
+        TokenRented(ClassId, TokenId, AccountId, BlockNumber),
+        RentalEnded(ClassId, TokenId, AccountId),
+        Approved(ClassId, TokenId, AccountId, Option<BlockNumber>),
+        ApprovalCanceled(ClassId, TokenId),
+        ApprovalForAllSet(ClassId, AccountId, AccountId, bool),
+        CollectionSettingsSet(ClassId, CollectionSettings),
+        TokenMetadataUpdated(ClassId, TokenId),
+        MaxSupplySet(ClassId, TokenId),
+        MintConfigSet(ClassId),
+        MintConfigCleared(ClassId),
+        MintAllowlistUpdated(ClassId, u32, bool),
+        MintPhaseStarted(ClassId, MintPhase),
+        PublicMint(ClassId, TokenId, AccountId, Balance),
+        ClaimRootSet(ClassId, Option<[u8; 32]>),
+        MintClaimed(ClassId, TokenId, AccountId),
+        ListingCanceled(ClassId, TokenId),
+        TokenSold(ClassId, TokenId, AccountId, AccountId, Balance, CurrencyId),
+        AuctionCreated(ClassId, TokenId, Balance, BlockNumber),
+        BidPlaced(ClassId, TokenId, AccountId, Balance),
+        AuctionExtended(ClassId, TokenId, BlockNumber),
+        AuctionSettled(ClassId, TokenId, AccountId, Balance),
+        AuctionEndedWithoutSale(ClassId, TokenId),
+        AuctionCanceled(ClassId, TokenId),
+        DutchAuctionStarted(ClassId, TokenId, Balance, Balance, BlockNumber),
+        DutchAuctionPurchased(ClassId, TokenId, AccountId, Balance),
+        DutchAuctionExpired(ClassId, TokenId),
+        SealedAuctionCreated(ClassId, TokenId, BlockNumber, BlockNumber),
+        BidCommitted(ClassId, TokenId, AccountId),
+        BidRevealed(ClassId, TokenId, AccountId, Balance),
+        BidDepositSlashed(ClassId, TokenId, AccountId, Balance),
+        CollectionRoyaltySet(ClassId, AccountId, Perbill),
+        TokenRoyaltySet(ClassId, TokenId, AccountId, Perbill),
+        RoyaltyPaid(ClassId, TokenId, AccountId, Balance),
+        MarketplaceFeeSet(Perbill),
+        MarketplaceFeePaid(ClassId, TokenId, Balance),
+        OfferExpired(ClassId, TokenId, AccountId),
+        CollectionOfferCreated(ClassId, AccountId, Balance, u32),
+        CollectionOfferCanceled(ClassId, AccountId),
+        CollectionOfferFilled(ClassId, TokenId, AccountId, AccountId, Balance),
+        OfferCountered(ClassId, TokenId, AccountId, Balance),
+        OfferRaised(ClassId, TokenId, AccountId, Balance),
+        CounterOfferAccepted(ClassId, TokenId, AccountId, AccountId, Balance),
+        AcceptedCurrenciesSet(ClassId, TokenId, Vec<CurrencyId>),
+        BundleListed(BundleId, AccountId, Balance),
+        BundleListingCanceled(BundleId),
+        BundleSold(BundleId, AccountId, AccountId, Balance),
+        BundleOfferCreated(BundleId, AccountId, Balance),
+        BundleOfferCanceled(BundleId),
+        BundleOfferAccepted(BundleId, AccountId, AccountId, Balance),
+        OfferRefunded(ClassId, TokenId, AccountId),
+        OfferUpdated(ClassId, TokenId, AccountId, Balance),
+        SaleSettingsSet(ClassId, TokenId, CurrencyId, Option<Balance>, Option<Balance>),
+        SaleSettingsCleared(ClassId, TokenId),
+        AllOffersCanceled(AccountId, u32),
     }
 );
 
@@ -107,7 +562,98 @@ decl_storage! {
         pub TokenExtendedInfo get(fn token_extended_info): double_map
             hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<ExtendedInfo>;
         pub Offers get(fn offer): double_map
-            hasher(twox_64_concat) (T::ClassId, T::TokenId), hasher(twox_64_concat) T::AccountId => Option<BalanceOf<T>>;
+            hasher(twox_64_concat) (T::ClassId, T::TokenId), hasher(twox_64_concat) T::AccountId => Option<OfferOf<T>>;
+        pub BidderOffers get(fn bidder_offers): map
+            hasher(blake2_128_concat) T::AccountId => Vec<(T::ClassId, T::TokenId)>;
+        pub OfferCount get(fn offer_count): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => u32;
+        pub OfferExpiries get(fn offer_expiries): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) ((T::ClassId, T::TokenId), T::AccountId) => Option<()>;
+        pub CollectionOffers get(fn collection_offer): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::AccountId => Option<CollectionOfferOf<T>>;
+        pub Listings get(fn listing): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<ListingOf<T>>;
+        pub NextBundleId get(fn next_bundle_id): BundleId;
+        pub BundleListings get(fn bundle_listing): map hasher(twox_64_concat) BundleId => Option<BundleOf<T>>;
+        /// The bundle listing a token is part of. A token can be in one bundle listing at a time.
+        pub BundledTokens get(fn bundled_in): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<BundleId>;
+        pub BundleOffers get(fn bundle_offer): map hasher(twox_64_concat) BundleId => Option<BundleOf<T>>;
+        /// Accounts allowed to buy a privately listed token.
+        pub ListingBuyers get(fn listing_buyers): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<Vec<T::AccountId>>;
+        pub EscrowListings get(fn escrow_listing): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => bool;
+        pub Escrows get(fn escrow): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<EscrowOf<T>>;
+        pub EscrowReleases get(fn escrow_releases): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+        pub InstallmentPlans get(fn installment_plan): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<InstallmentPlanOf<T>>;
+        pub InstallmentDeadlines get(fn installment_deadlines): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+        pub RentalOffers get(fn rental_terms): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<RentalTermsOf<T>>;
+        /// The renter currently holding the user role on a token, and the block it ends at.
+        pub TokenUsers get(fn token_user): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<(T::AccountId, T::BlockNumber)>;
+        pub RentalEndings get(fn rental_endings): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+        /// The account approved to manage a token for its owner, and when the approval lapses.
+        pub TokenApprovals get(fn approval): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<(T::AccountId, Option<T::BlockNumber>)>;
+        /// Operators approved by an owner for all of their tokens in a collection.
+        pub CollectionOperators get(fn collection_operator): double_map
+            hasher(blake2_128_concat) (T::ClassId, T::AccountId), hasher(blake2_128_concat) T::AccountId => Option<Option<T::BlockNumber>>;
+        pub TokenSaleSettings get(fn sale_settings): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<SaleSettingsOf<T>>;
+        /// Currencies a token's owner accepts for offers and listings. Missing means any.
+        pub AcceptedCurrencies get(fn accepted_currencies): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<Vec<CurrencyIdOf<T>>>;
+        pub Auctions get(fn auction): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<EnglishAuctionOf<T>>;
+        pub AuctionEndings get(fn auction_endings): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+        pub DutchAuctions get(fn dutch_auction): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<DutchAuctionOf<T>>;
+        pub DutchAuctionEndings get(fn dutch_auction_endings): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+        pub SealedAuctions get(fn sealed_auction): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<SealedAuctionOf<T>>;
+        pub SealedBids get(fn sealed_bid): double_map
+            hasher(twox_64_concat) (T::ClassId, T::TokenId), hasher(twox_64_concat) T::AccountId => Option<SealedBidOf<T>>;
+        pub SealedAuctionEndings get(fn sealed_auction_endings): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+        /// Oldest block a queue still has entries for, when it fell behind `MaxSettlementsPerBlock`.
+        pub SettlementBacklog get(fn settlement_backlog): map
+            hasher(twox_64_concat) SettlementQueue => Option<T::BlockNumber>;
+        /// Fee rate set by governance, overriding `Config::MarketplaceFee`.
+        pub MarketplaceFeeRate get(fn marketplace_fee_rate): Option<Perbill>;
+        pub MintConfigs get(fn mint_config): map
+            hasher(twox_64_concat) T::ClassId => Option<MintConfigOf<T>>;
+        pub MintAllowlist get(fn mint_allowlist): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(blake2_128_concat) T::AccountId => bool;
+        pub MintedBy get(fn minted_by): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(blake2_128_concat) T::AccountId => u32;
+        /// Merkle root over `(account, quantity)` leaves of accounts allowed to claim tokens.
+        pub ClaimRoots get(fn claim_root): map
+            hasher(twox_64_concat) T::ClassId => Option<[u8; 32]>;
+        pub Claimed get(fn claimed): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(blake2_128_concat) T::AccountId => u32;
+        pub MintPhaseChanges get(fn mint_phase_changes): double_map
+            hasher(twox_64_concat) T::BlockNumber, hasher(twox_64_concat) T::ClassId => Option<MintPhase>;
+        pub MaxSupply get(fn max_supply): map
+            hasher(twox_64_concat) T::ClassId => Option<T::TokenId>;
+        pub CollectionFlags get(fn collection_settings): map
+            hasher(twox_64_concat) T::ClassId => CollectionSettings;
+        /// Tokens of a collection in an auction, escrow or installment plan that still has to
+        /// move them. Transfers can't be disabled while any are pending.
+        pub PendingSales get(fn pending_sales): map
+            hasher(twox_64_concat) T::ClassId => u32;
+        pub CollectionRoyalties get(fn collection_royalty): map
+            hasher(twox_64_concat) T::ClassId => Option<Royalty<T::AccountId>>;
+        pub TokenRoyalties get(fn token_royalty): double_map
+            hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<Royalty<T::AccountId>>;
     }
 }
 
@@ -117,6 +663,76 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        const AuctionExtendPeriod: T::BlockNumber = T::AuctionExtendPeriod::get();
+        const MaxSealedBids: u32 = T::MaxSealedBids::get();
+        const UnrevealedBidSlash: Perbill = T::UnrevealedBidSlash::get();
+        const MaxRoyalty: Perbill = T::MaxRoyalty::get();
+        const MaxMarketplaceFee: Perbill = T::MaxMarketplaceFee::get();
+        const MaxOfferExpiriesPerBlock: u32 = T::MaxOfferExpiriesPerBlock::get();
+        const MaxSettlementsPerBlock: u32 = T::MaxSettlementsPerBlock::get();
+        const OfferReapReward: BalanceOf<T> = T::OfferReapReward::get();
+        const MaxNegotiationRounds: u32 = T::MaxNegotiationRounds::get();
+        const NativeCurrencyId: CurrencyIdOf<T> = T::NativeCurrencyId::get();
+        const MaxAcceptedCurrencies: u32 = T::MaxAcceptedCurrencies::get();
+        const MaxBundleSize: u32 = T::MaxBundleSize::get();
+        const MaxOffersPerToken: u32 = T::MaxOffersPerToken::get();
+        const MaxOffersPerAccount: u32 = T::MaxOffersPerAccount::get();
+        const MaxPrivateBuyers: u32 = T::MaxPrivateBuyers::get();
+        const EscrowModuleId: ModuleId = T::EscrowModuleId::get();
+        const EscrowPeriod: T::BlockNumber = T::EscrowPeriod::get();
+        const MaxInstallments: u32 = T::MaxInstallments::get();
+        const MaxAllowlistBatch: u32 = T::MaxAllowlistBatch::get();
+        const MaxProofLength: u32 = T::MaxProofLength::get();
+
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let db = T::DbWeight::get();
+            let limit = Weight::from(T::MaxSettlementsPerBlock::get());
+            // every queue reads its backlog and up to `limit` block prefixes, then updates the backlog.
+            // A failed settlement is rolled back and left for anyone to retry with `settle`.
+            let mut weight = db.reads_writes(7 * (1 + limit), 7);
+
+            for ((collection_id, token_id), _) in Self::due_settlements::<AuctionEndings<T>, _, _>(SettlementQueue::Auctions, now) {
+                let _ = Self::settle_auction(collection_id, token_id);
+                weight = weight.saturating_add(Self::sale_weight());
+            }
+            for ((collection_id, token_id), _) in Self::due_settlements::<DutchAuctionEndings<T>, _, _>(SettlementQueue::DutchAuctions, now) {
+                Self::expire_dutch_auction(collection_id, token_id);
+                weight = weight.saturating_add(db.reads_writes(1, 3));
+            }
+            for ((collection_id, token_id), _) in Self::due_settlements::<SealedAuctionEndings<T>, _, _>(SettlementQueue::SealedAuctions, now) {
+                let _ = Self::settle_sealed_auction(collection_id, token_id);
+                let bids = Weight::from(T::MaxSealedBids::get());
+                weight = weight.saturating_add(Self::sale_weight()).saturating_add(db.reads_writes(2 * bids, 2 * bids));
+            }
+            for ((collection_id, token_id), _) in Self::due_settlements::<InstallmentDeadlines<T>, _, _>(SettlementQueue::Installments, now) {
+                let _ = Self::default_installment_plan(collection_id, token_id);
+                weight = weight.saturating_add(db.reads_writes(4, 5));
+            }
+            for ((collection_id, token_id), _) in Self::due_settlements::<RentalEndings<T>, _, _>(SettlementQueue::Rentals, now) {
+                Self::end_rental(collection_id, token_id);
+                weight = weight.saturating_add(db.reads_writes(2, 3));
+            }
+            for (collection_id, phase) in Self::due_settlements::<MintPhaseChanges<T>, _, _>(SettlementQueue::MintPhases, now) {
+                Self::deposit_event(RawEvent::MintPhaseStarted(collection_id, phase));
+                weight = weight.saturating_add(db.writes(1));
+            }
+            for ((collection_id, token_id), _) in Self::due_settlements::<EscrowReleases<T>, _, _>(SettlementQueue::Escrows, now) {
+                let _ = Self::release_escrow(collection_id, token_id);
+                weight = weight.saturating_add(Self::sale_weight());
+            }
+            // anything past the limit stays queued and can be reaped with `reap_expired_offer`
+            let expiries = OfferExpiries::<T>::drain_prefix(now)
+                .take(T::MaxOfferExpiriesPerBlock::get() as usize);
+            for (((collection_id, token_id), buyer), _) in expiries {
+                if let Some(offer) = Self::remove_offer(collection_id, token_id, &buyer) {
+                    Self::unreserve_funds(offer.currency_id, &buyer, offer.amount);
+                    Self::deposit_event(RawEvent::OfferExpired(collection_id, token_id, buyer));
+                }
+                weight = weight.saturating_add(db.reads_writes(4, 5));
+            }
+            weight
+        }
+
         #[weight = T::BlockWeights::get().max_block / 100]
         pub fn set_curator(origin, curator: T::AccountId) -> DispatchResult {
             ensure_root(origin)?;
@@ -125,13 +741,253 @@ decl_module! {
         }
 
         #[weight = T::BlockWeights::get().max_block / 100]
-        pub fn create_collection(origin, metadata: Vec<u8>, class_data: T::ClassData) -> DispatchResult {
+        pub fn set_marketplace_fee(origin, fee: Perbill) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(fee <= T::MaxMarketplaceFee::get(), Error::<T>::FeeTooHigh);
+            MarketplaceFeeRate::put(fee);
+            Self::deposit_event(RawEvent::MarketplaceFeeSet(fee));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn create_collection(origin,
+            metadata: Vec<u8>,
+            class_data: T::ClassData,
+            max_supply: Option<T::TokenId>) -> DispatchResult {
             let who = ensure_signed(origin)?;
             let collection_id = nft::Pallet::<T>::create_class(&who, metadata, class_data)?;
+            if let Some(max_supply) = max_supply {
+                MaxSupply::<T>::insert(collection_id, max_supply);
+            }
             Self::deposit_event(RawEvent::CollectionCreated(collection_id));
             Ok(())
         }
 
+        /// Open, reschedule or cancel a public sale for a collection. The windows must start in
+        /// the future; pass the same block for `allowlist_start` and `public_start` to skip the
+        /// allowlist phase.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_mint_config(origin,
+            collection_id: T::ClassId,
+            config: Option<MintConfigOf<T>>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            if let Some(config) = &config {
+                ensure!(
+                    config.allowlist_start > frame_system::Pallet::<T>::block_number()
+                        && config.allowlist_start <= config.public_start
+                        && config.public_start < config.end,
+                    Error::<T>::InvalidMintWindow
+                );
+            }
+
+            if let Some(previous) = MintConfigs::<T>::take(collection_id) {
+                MintPhaseChanges::<T>::remove(previous.allowlist_start, collection_id);
+                MintPhaseChanges::<T>::remove(previous.public_start, collection_id);
+                MintPhaseChanges::<T>::remove(previous.end, collection_id);
+            }
+            match config {
+                Some(config) => {
+                    if config.allowlist_start < config.public_start {
+                        MintPhaseChanges::<T>::insert(config.allowlist_start, collection_id, MintPhase::Allowlist);
+                    }
+                    MintPhaseChanges::<T>::insert(config.public_start, collection_id, MintPhase::Public);
+                    MintPhaseChanges::<T>::insert(config.end, collection_id, MintPhase::Closed);
+                    MintConfigs::<T>::insert(collection_id, config);
+                    Self::deposit_event(RawEvent::MintConfigSet(collection_id));
+                }
+                None => Self::deposit_event(RawEvent::MintConfigCleared(collection_id)),
+            }
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn update_mint_allowlist(origin,
+            collection_id: T::ClassId,
+            accounts: Vec<T::AccountId>,
+            allowed: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            ensure!(accounts.len() as u32 <= T::MaxAllowlistBatch::get(), Error::<T>::TooManyAccounts);
+
+            for account in accounts.iter() {
+                if allowed {
+                    MintAllowlist::<T>::insert(collection_id, account, true);
+                } else {
+                    MintAllowlist::<T>::remove(collection_id, account);
+                }
+            }
+            Self::deposit_event(RawEvent::MintAllowlistUpdated(collection_id, accounts.len() as u32, allowed));
+            Ok(())
+        }
+
+        /// Buy a newly minted token during a collection's public sale.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn public_mint(origin, collection_id: T::ClassId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+            let config = MintConfigs::<T>::get(collection_id).ok_or(Error::<T>::MintNotOpen)?;
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(now >= config.allowlist_start, Error::<T>::MintNotOpen);
+            ensure!(now < config.end, Error::<T>::MintClosed);
+            if now < config.public_start {
+                ensure!(MintAllowlist::<T>::get(collection_id, &who), Error::<T>::NotOnAllowlist);
+            }
+            let minted = MintedBy::<T>::get(collection_id, &who);
+            ensure!(minted < config.per_account_limit, Error::<T>::MintLimitReached);
+            Self::ensure_below_max_supply(collection_id)?;
+
+            T::Currency::transfer(&who, &config.beneficiary, config.price, ExistenceRequirement::KeepAlive)?;
+            let token_id = nft::Pallet::<T>::mint(&who, collection_id, Vec::new(), TokenData::default())?;
+            MintedBy::<T>::insert(collection_id, &who, minted + 1);
+            Self::deposit_event(RawEvent::TokenMinted(collection_id, token_id));
+            Self::deposit_event(RawEvent::PublicMint(collection_id, token_id, who, config.price));
+            Ok(())
+        }
+
+        /// Commit the Merkle root of a claim list, or close claims with `None`. Amounts already
+        /// claimed are kept, so a new root can't be used to claim twice.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_claim_root(origin,
+            collection_id: T::ClassId,
+            root: Option<[u8; 32]>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+
+            match root {
+                Some(root) => ClaimRoots::<T>::insert(collection_id, root),
+                None => ClaimRoots::<T>::remove(collection_id),
+            }
+            Self::deposit_event(RawEvent::ClaimRootSet(collection_id, root));
+            Ok(())
+        }
+
+        /// Mint one token against a `(caller, quantity)` leaf of the collection's claim list.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn claim_mint(origin,
+            collection_id: T::ClassId,
+            quantity: u32,
+            proof: Vec<[u8; 32]>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+            let root = ClaimRoots::<T>::get(collection_id).ok_or(Error::<T>::MintNotOpen)?;
+
+            ensure!(proof.len() as u32 <= T::MaxProofLength::get(), Error::<T>::InvalidProof);
+            let leaf = sp_io::hashing::blake2_256(&(&who, quantity).encode());
+            ensure!(Self::merkle_root(leaf, &proof) == root, Error::<T>::InvalidProof);
+
+            let claimed = Claimed::<T>::get(collection_id, &who);
+            ensure!(claimed < quantity, Error::<T>::MintLimitReached);
+            Self::ensure_below_max_supply(collection_id)?;
+
+            let token_id = nft::Pallet::<T>::mint(&who, collection_id, Vec::new(), TokenData::default())?;
+            Claimed::<T>::insert(collection_id, &who, claimed + 1);
+            Self::deposit_event(RawEvent::TokenMinted(collection_id, token_id));
+            Self::deposit_event(RawEvent::MintClaimed(collection_id, token_id, who));
+            Ok(())
+        }
+
+        /// Cap the number of tokens a collection can ever mint. Once set, the cap is final.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_max_supply(origin,
+            collection_id: T::ClassId,
+            max_supply: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            ensure!(!MaxSupply::<T>::contains_key(collection_id), Error::<T>::MaxSupplyAlreadySet);
+            ensure!(max_supply >= nft::NextTokenId::<T>::get(collection_id), Error::<T>::InvalidMaxSupply);
+
+            MaxSupply::<T>::insert(collection_id, max_supply);
+            Self::deposit_event(RawEvent::MaxSupplySet(collection_id, max_supply));
+            Ok(())
+        }
+
+        /// Restrict what may be done with a collection's tokens. Settings can only become
+        /// stricter.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_collection_settings(origin,
+            collection_id: T::ClassId,
+            settings: CollectionSettings) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            let current = Self::collection_settings(collection_id);
+            ensure!(settings.is_stricter_than(&current), Error::<T>::InvalidSettings);
+            if current.allows(CollectionSettings::TRANSFERABLE) && !settings.allows(CollectionSettings::TRANSFERABLE) {
+                ensure!(PendingSales::<T>::get(collection_id) == 0, Error::<T>::SalesPending);
+            }
+
+            CollectionFlags::<T>::insert(collection_id, settings);
+            Self::deposit_event(RawEvent::CollectionSettingsSet(collection_id, settings));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn update_token_metadata(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            metadata: Vec<u8>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            Self::ensure_setting(collection_id, CollectionSettings::METADATA_MUTABLE, Error::<T>::MetadataLocked)?;
+
+            nft::Tokens::<T>::try_mutate(collection_id, token_id, |token| -> DispatchResult {
+                let token = token.as_mut().ok_or(Error::<T>::TokenNotFound)?;
+                token.metadata = metadata;
+                Ok(())
+            })?;
+            Self::deposit_event(RawEvent::TokenMetadataUpdated(collection_id, token_id));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_collection_royalty(origin,
+            collection_id: T::ClassId,
+            beneficiary: T::AccountId,
+            rate: Perbill) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            ensure!(rate <= T::MaxRoyalty::get(), Error::<T>::RoyaltyTooHigh);
+
+            CollectionRoyalties::<T>::insert(collection_id, Royalty { beneficiary: beneficiary.clone(), rate });
+            Self::deposit_event(RawEvent::CollectionRoyaltySet(collection_id, beneficiary, rate));
+            Ok(())
+        }
+
+        /// Override the collection royalty for a single token.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_token_royalty(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            beneficiary: T::AccountId,
+            rate: Perbill) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            ensure!(nft::Pallet::<T>::tokens(collection_id, token_id).is_some(), Error::<T>::TokenNotFound);
+            ensure!(rate <= T::MaxRoyalty::get(), Error::<T>::RoyaltyTooHigh);
+
+            TokenRoyalties::<T>::insert(collection_id, token_id, Royalty { beneficiary: beneficiary.clone(), rate });
+            Self::deposit_event(RawEvent::TokenRoyaltySet(collection_id, token_id, beneficiary, rate));
+            Ok(())
+        }
+
         #[weight = T::BlockWeights::get().max_block / 100]
         pub fn mint(origin,
                 collection_id: T::ClassId,
@@ -141,6 +997,7 @@ decl_module! {
             let who = ensure_signed(origin)?;
             let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
             ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            Self::ensure_below_max_supply(collection_id)?;
 
             //T::Currency::set_lock(PALLET_ID, &who, T::DefaultCost::get(), WithdrawReasons::all());
             // agree there needs to be some cost but I'm not certain it should be via lock since tokens
@@ -150,6 +1007,25 @@ decl_module! {
             Ok(())
         }
 
+        /// Mint a token straight to `recipient`, which is how soulbound tokens reach their
+        /// holders.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn mint_to(origin,
+                collection_id: T::ClassId,
+                recipient: T::AccountId,
+                metadata: Vec<u8>,
+                token_data: T::TokenData
+            ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+            ensure!(collection.owner == who, Error::<T>::NotCollectionOwner);
+            Self::ensure_below_max_supply(collection_id)?;
+
+            let token_id = nft::Pallet::<T>::mint(&recipient, collection_id, metadata, token_data)?;
+            Self::deposit_event(RawEvent::TokenMinted(collection_id, token_id));
+            Ok(())
+        }
+
         #[weight = T::BlockWeights::get().max_block / 100]
         pub fn appreciate(origin,
             collection_id: T::ClassId,
@@ -174,7 +1050,11 @@ decl_module! {
             let who = ensure_signed(origin)?;
             let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
 
-            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            // while a rental lasts the display is the renter's to control
+            match TokenUsers::<T>::get(collection_id, token_id) {
+                Some((user, _)) => ensure!(user == who, Error::<T>::NotTokenUser),
+                None => Self::ensure_owner_or_approved(&who, collection_id, token_id, &token.owner)?,
+            }
 
             let mut info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
                 display_flag: false,
@@ -198,7 +1078,9 @@ decl_module! {
             let who = ensure_signed(origin)?;
             let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
 
-            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            Self::ensure_owner_or_approved(&who, collection_id, token_id, &token.owner)?;
+            // a token is only shown for sale with a price, which `list_token` sets
+            ensure!(!for_sale || Listings::<T>::contains_key(collection_id, token_id), Error::<T>::ListingNotFound);
 
             let mut info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
                 display_flag: false,
@@ -207,113 +1089,1258 @@ decl_module! {
                 frozen: false
             });
 
-            info.display_flag = display;
+            // while a rental lasts the display is the renter's to control
+            if !TokenUsers::<T>::contains_key(collection_id, token_id) {
+                info.display_flag = display;
+            }
+            let display = info.display_flag;
+            info.display_for_sale = for_sale;
             TokenExtendedInfo::<T>::insert(collection_id, token_id, info);
+            if !for_sale {
+                Self::remove_listing(collection_id, token_id);
+            }
             Self::deposit_event(RawEvent::TokenDisplayToggled(collection_id, token_id, display));
             Ok(())
         }
 
         #[weight = T::BlockWeights::get().max_block / 100]
-        pub fn transfer(origin,
-                collection_id: T::ClassId,
-                token_id: T::TokenId,
-                recipient: T::AccountId) -> DispatchResult {
+        pub fn list_token(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            price: BalanceOf<T>,
+            currency_id: CurrencyIdOf<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
-
-            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
-
-            let info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
-                display_flag: false,
-                report: ReportReason::None,
-                frozen: false,
-                display_for_sale: false
-            });
-
-            ensure!(info.frozen == false, Error::<T>::TokenFrozen);
 
-            nft::Pallet::<T>::transfer(&who, &recipient, (collection_id, token_id))?;
-            Self::deposit_event(RawEvent::TokenTransferred(collection_id, token_id, recipient));
+            Self::insert_listing(&who, collection_id, token_id, price, currency_id)?;
+            ListingBuyers::<T>::remove(collection_id, token_id);
+            Self::deposit_event(RawEvent::TokenListed(collection_id, token_id, price, currency_id));
             Ok(())
         }
 
+        /// List a token that only the given accounts may buy through `buy_now`.
         #[weight = T::BlockWeights::get().max_block / 100]
-        pub fn create_offer(origin,
+        pub fn list_token_privately(origin,
             collection_id: T::ClassId,
             token_id: T::TokenId,
-            price: BalanceOf<T>) -> DispatchResult {
+            price: BalanceOf<T>,
+            currency_id: CurrencyIdOf<T>,
+            buyers: Vec<T::AccountId>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            T::Currency::reserve(&who, price)?;
-            Offers::<T>::insert((collection_id, token_id), who.clone(), price);
-            Self::deposit_event(RawEvent::OfferCreated(collection_id, token_id, price, who));
+
+            ensure!(
+                !buyers.is_empty() && buyers.len() as u32 <= T::MaxPrivateBuyers::get(),
+                Error::<T>::InvalidBuyers
+            );
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            ensure!(!buyers.contains(&token.owner), Error::<T>::InvalidBuyers);
+
+            Self::insert_listing(&who, collection_id, token_id, price, currency_id)?;
+            ListingBuyers::<T>::insert(collection_id, token_id, buyers.clone());
+            Self::deposit_event(RawEvent::TokenListedPrivately(collection_id, token_id, price, currency_id, buyers));
             Ok(())
         }
 
         #[weight = T::BlockWeights::get().max_block / 100]
-        pub fn accept_offer(origin,
+        pub fn cancel_listing(origin,
             collection_id: T::ClassId,
-            token_id: T::TokenId,
-            buyer_address: T::AccountId) -> DispatchResult {
+            token_id: T::TokenId) -> DispatchResult {
             let who = ensure_signed(origin)?;
             let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
 
-            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
-
-            let info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
-                display_flag: false,
-                report: ReportReason::None,
-                frozen: false,
-                display_for_sale: false
-            });
-
-            ensure!(info.frozen == false, Error::<T>::TokenFrozen);
+            Self::ensure_owner_or_approved(&who, collection_id, token_id, &token.owner)?;
+            ensure!(Listings::<T>::contains_key(collection_id, token_id), Error::<T>::ListingNotFound);
 
-            if let Some(offer) = Offers::<T>::get((collection_id, token_id), buyer_address.clone()){
-                T::Currency::repatriate_reserved(&buyer_address, &who, offer, BalanceStatus::Free)?;
-                Offers::<T>::remove((collection_id, token_id), who.clone());
-                nft::Pallet::<T>::transfer(&who, &buyer_address, (collection_id, token_id))?;
-                Self::deposit_event(RawEvent::OfferAccepted(collection_id, token_id, who, buyer_address));
-                Ok(())
-            } else {
-                fail!(Error::<T>::OfferNotFound);
-            }
+            Self::remove_listing(collection_id, token_id);
+            Ok(())
         }
 
         #[weight = T::BlockWeights::get().max_block / 100]
-        pub fn cancel_offer(origin,
+        #[transactional]
+        pub fn buy_now(origin,
             collection_id: T::ClassId,
             token_id: T::TokenId) -> DispatchResult {
             let who = ensure_signed(origin)?;
             let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let listing = Listings::<T>::get(collection_id, token_id).ok_or(Error::<T>::ListingNotFound)?;
+            let price = listing.price;
 
-            if let Some(offer) = Offers::<T>::get((collection_id, token_id), who.clone()){
-                T::Currency::unreserve(&who, offer);
-                Offers::<T>::remove((collection_id, token_id), who.clone());
-                Self::deposit_event(RawEvent::OfferCanceled(collection_id, token_id, token.owner, who));
-                Ok(())
+            ensure!(token.owner != who, Error::<T>::CannotBuyOwnToken);
+            if let Some(buyers) = ListingBuyers::<T>::get(collection_id, token_id) {
+                ensure!(buyers.contains(&who), Error::<T>::NotAllowedBuyer);
+            }
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+            ensure!(Self::free_balance(listing.currency_id, &who) >= price, Error::<T>::LowBalance);
+
+            let escrowed = EscrowListings::<T>::get(collection_id, token_id);
+            if escrowed {
+                // the price may have been lowered since escrow was turned on
+                ensure!(price >= Self::minimum_balance(listing.currency_id), Error::<T>::EscrowTooSmall);
+                Self::pay(listing.currency_id, &who, &Self::escrow_account(), price, false)?;
             } else {
-                fail!(Error::<T>::OfferNotFound);
+                Self::pay_for_token(collection_id, token_id, listing.currency_id, &who, &token.owner, price, false)?;
+            }
+            Self::transfer_token(&token.owner, &who, collection_id, token_id)?;
+            Self::deposit_event(RawEvent::TokenSold(
+                collection_id,
+                token_id,
+                token.owner.clone(),
+                who.clone(),
+                price,
+                listing.currency_id,
+            ));
+
+            if escrowed {
+                let release_at = frame_system::Pallet::<T>::block_number() + T::EscrowPeriod::get();
+                Escrows::<T>::insert(collection_id, token_id, Escrow {
+                    seller: token.owner,
+                    buyer: who,
+                    price,
+                    currency_id: listing.currency_id,
+                    release_at,
+                    disputed: false,
+                });
+                EscrowReleases::<T>::insert(release_at, (collection_id, token_id), ());
+                Self::set_frozen(collection_id, token_id, true);
+                Self::set_sale_pending(collection_id, true);
+                Self::deposit_event(RawEvent::EscrowOpened(collection_id, token_id, release_at));
             }
+            Ok(())
         }
 
+        /// Require the payment for a listed token to be held in escrow after the sale. Relisting
+        /// the token turns escrow off again.
         #[weight = T::BlockWeights::get().max_block / 100]
-        pub fn report(origin,
+        pub fn set_listing_escrow(origin,
             collection_id: T::ClassId,
             token_id: T::TokenId,
-            reason: ReportReason) -> DispatchResult {
-            ensure_signed(origin)?;
-            ensure!(nft::Pallet::<T>::tokens(collection_id, token_id).is_some(), Error::<T>::TokenNotFound);
+            escrowed: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
 
-            let mut info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
-                display_flag: false,
-                report: ReportReason::None,
-                frozen: false,
-                display_for_sale: false
-            });
+            Self::ensure_owner_or_approved(&who, collection_id, token_id, &token.owner)?;
+            let listing = Listings::<T>::get(collection_id, token_id).ok_or(Error::<T>::ListingNotFound)?;
+            // the pooled escrow account must never be left holding less than the existential deposit
+            ensure!(
+                !escrowed || listing.price >= Self::minimum_balance(listing.currency_id),
+                Error::<T>::EscrowTooSmall
+            );
 
-            info.report = reason.clone();
-            TokenExtendedInfo::<T>::insert(collection_id, token_id, info);
-            Self::deposit_event(RawEvent::ReportReceived(collection_id, token_id, reason));
+            EscrowListings::<T>::insert(collection_id, token_id, escrowed);
+            Self::deposit_event(RawEvent::ListingEscrowSet(collection_id, token_id, escrowed));
+            Ok(())
+        }
+
+        /// Hold an escrowed payment until the curator resolves the dispute.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn open_dispute(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut escrow = Escrows::<T>::get(collection_id, token_id).ok_or(Error::<T>::EscrowNotFound)?;
+
+            ensure!(escrow.buyer == who, Error::<T>::NotEscrowBuyer);
+            ensure!(!escrow.disputed, Error::<T>::AlreadyDisputed);
+            ensure!(frame_system::Pallet::<T>::block_number() < escrow.release_at, Error::<T>::DisputeWindowClosed);
+
+            EscrowReleases::<T>::remove(escrow.release_at, (collection_id, token_id));
+            escrow.disputed = true;
+            Escrows::<T>::insert(collection_id, token_id, escrow);
+            Self::deposit_event(RawEvent::EscrowDisputed(collection_id, token_id, who));
+            Ok(())
+        }
+
+        /// Settle a disputed sale, either paying the seller or returning the token and the
+        /// payment.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn resolve_dispute(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            reverse: bool) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Curator::<T>::get() == who, Error::<T>::NotCurator);
+
+            let escrow = Escrows::<T>::get(collection_id, token_id).ok_or(Error::<T>::EscrowNotFound)?;
+            ensure!(escrow.disputed, Error::<T>::NotDisputed);
+
+            if reverse {
+                Escrows::<T>::remove(collection_id, token_id);
+                Self::pay(escrow.currency_id, &Self::escrow_account(), &escrow.buyer, escrow.price, false)?;
+                Self::set_frozen(collection_id, token_id, false);
+                Self::set_sale_pending(collection_id, false);
+                Self::transfer_token(&escrow.buyer, &escrow.seller, collection_id, token_id)?;
+                Self::deposit_event(RawEvent::SaleReversed(collection_id, token_id, escrow.buyer, escrow.price));
+                Ok(())
+            } else {
+                Self::release_escrow(collection_id, token_id)
+            }
+        }
+
+        /// Offer a token to `buyer` for `price`, paid as a deposit followed by `installments`
+        /// equal payments, one every `period` blocks.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn create_installment_plan(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            buyer: T::AccountId,
+            price: BalanceOf<T>,
+            deposit: BalanceOf<T>,
+            installments: u32,
+            period: T::BlockNumber,
+            forfeit: Perbill) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            Self::ensure_transferable(collection_id)?;
+            ensure!(buyer != who, Error::<T>::CannotBuyOwnToken);
+            ensure!(!InstallmentPlans::<T>::contains_key(collection_id, token_id), Error::<T>::PlanAlreadyExists);
+            ensure!(
+                deposit < price
+                    && installments > 0
+                    && installments <= T::MaxInstallments::get()
+                    && !period.is_zero(),
+                Error::<T>::InvalidPlan
+            );
+
+            InstallmentPlans::<T>::insert(collection_id, token_id, InstallmentPlan {
+                seller: who,
+                buyer: buyer.clone(),
+                price,
+                deposit,
+                paid: Zero::zero(),
+                installments,
+                period,
+                next_due: None,
+                forfeit,
+            });
+            Self::deposit_event(RawEvent::InstallmentPlanCreated(collection_id, token_id, buyer, price));
+            Ok(())
+        }
+
+        /// Withdraw an installment plan the buyer has not started yet.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_installment_plan(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let plan = InstallmentPlans::<T>::get(collection_id, token_id).ok_or(Error::<T>::PlanNotFound)?;
+
+            ensure!(plan.seller == who, Error::<T>::NotTokenOwner);
+            ensure!(plan.next_due.is_none(), Error::<T>::PlanAlreadyStarted);
+
+            InstallmentPlans::<T>::remove(collection_id, token_id);
+            Self::deposit_event(RawEvent::InstallmentPlanCanceled(collection_id, token_id));
+            Ok(())
+        }
+
+        /// Pay the deposit and lock the token for the length of the plan.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn start_installment_plan(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let mut plan = InstallmentPlans::<T>::get(collection_id, token_id).ok_or(Error::<T>::PlanNotFound)?;
+
+            ensure!(plan.buyer == who, Error::<T>::NotPlanBuyer);
+            ensure!(plan.next_due.is_none(), Error::<T>::PlanAlreadyStarted);
+            ensure!(token.owner == plan.seller, Error::<T>::NotTokenOwner);
+            Self::ensure_transferable(collection_id)?;
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+
+            T::Currency::reserve(&who, plan.deposit)?;
+            let next_due = frame_system::Pallet::<T>::block_number() + plan.period;
+            plan.paid = plan.deposit;
+            plan.next_due = Some(next_due);
+
+            Self::remove_listing(collection_id, token_id);
+            Self::set_frozen(collection_id, token_id, true);
+            Self::set_sale_pending(collection_id, true);
+            InstallmentPlans::<T>::insert(collection_id, token_id, plan);
+            InstallmentDeadlines::<T>::insert(next_due, (collection_id, token_id), ());
+            Self::deposit_event(RawEvent::InstallmentPlanStarted(collection_id, token_id, who, next_due));
+            Ok(())
+        }
+
+        /// Pay the next installment. The final one completes the sale.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn pay_installment(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut plan = InstallmentPlans::<T>::get(collection_id, token_id).ok_or(Error::<T>::PlanNotFound)?;
+
+            ensure!(plan.buyer == who, Error::<T>::NotPlanBuyer);
+            let due = plan.next_due.ok_or(Error::<T>::PlanNotStarted)?;
+            // the deadline may still be queued if settlements are running behind
+            ensure!(frame_system::Pallet::<T>::block_number() < due, Error::<T>::InstallmentOverdue);
+
+            let outstanding = plan.price.saturating_sub(plan.paid);
+            let amount = outstanding / BalanceOf::<T>::from(plan.installments);
+            let amount = if plan.installments == 1 { outstanding } else { amount };
+            T::Currency::reserve(&who, amount)?;
+            plan.paid = plan.paid.saturating_add(amount);
+            plan.installments -= 1;
+            InstallmentDeadlines::<T>::remove(due, (collection_id, token_id));
+            Self::deposit_event(RawEvent::InstallmentPaid(collection_id, token_id, who.clone(), amount));
+
+            if plan.installments == 0 {
+                InstallmentPlans::<T>::remove(collection_id, token_id);
+                Self::set_frozen(collection_id, token_id, false);
+                Self::set_sale_pending(collection_id, false);
+                Self::pay_for_token(
+                    collection_id,
+                    token_id,
+                    T::NativeCurrencyId::get(),
+                    &who,
+                    &plan.seller,
+                    plan.price,
+                    true,
+                )?;
+                Self::transfer_token(&plan.seller, &who, collection_id, token_id)?;
+                Self::deposit_event(RawEvent::InstallmentPlanCompleted(collection_id, token_id, who, plan.price));
+            } else {
+                let next_due = due + plan.period;
+                plan.next_due = Some(next_due);
+                InstallmentPlans::<T>::insert(collection_id, token_id, plan);
+                InstallmentDeadlines::<T>::insert(next_due, (collection_id, token_id), ());
+            }
+            Ok(())
+        }
+
+        /// Let `operator` transfer, list and accept offers for a token on the owner's behalf
+        /// until `expires`. Replaces any earlier approval and is cleared when the token moves.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn approve(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            operator: T::AccountId,
+            expires: Option<T::BlockNumber>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            if let Some(expires) = expires {
+                ensure!(expires > frame_system::Pallet::<T>::block_number(), Error::<T>::InvalidExpiry);
+            }
+
+            TokenApprovals::<T>::insert(collection_id, token_id, (operator.clone(), expires));
+            Self::deposit_event(RawEvent::Approved(collection_id, token_id, operator, expires));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_approval(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+
+            TokenApprovals::<T>::remove(collection_id, token_id);
+            Self::deposit_event(RawEvent::ApprovalCanceled(collection_id, token_id));
+            Ok(())
+        }
+
+        /// Approve or revoke `operator` for every token the caller holds in a collection.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_approval_for_all(origin,
+            collection_id: T::ClassId,
+            operator: T::AccountId,
+            approved: bool,
+            expires: Option<T::BlockNumber>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+
+            if approved {
+                if let Some(expires) = expires {
+                    ensure!(expires > frame_system::Pallet::<T>::block_number(), Error::<T>::InvalidExpiry);
+                }
+                CollectionOperators::<T>::insert((collection_id, who.clone()), &operator, expires);
+            } else {
+                CollectionOperators::<T>::remove((collection_id, who.clone()), &operator);
+            }
+            Self::deposit_event(RawEvent::ApprovalForAllSet(collection_id, who, operator, approved));
+            Ok(())
+        }
+
+        /// Offer a token for rent at a price per block, for at most `max_duration` blocks.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn rent_out(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            price_per_block: BalanceOf<T>,
+            max_duration: T::BlockNumber) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            ensure!(!max_duration.is_zero(), Error::<T>::InvalidRentalDuration);
+
+            RentalOffers::<T>::insert(collection_id, token_id, RentalTerms { price_per_block, max_duration });
+            Self::deposit_event(RawEvent::RentalTermsSet(collection_id, token_id, price_per_block, max_duration));
+            Ok(())
+        }
+
+        /// Stop offering a token for rent. A rental already under way runs to its end.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_rent_out(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            ensure!(RentalOffers::<T>::contains_key(collection_id, token_id), Error::<T>::RentalNotFound);
+
+            RentalOffers::<T>::remove(collection_id, token_id);
+            Self::deposit_event(RawEvent::RentalTermsRemoved(collection_id, token_id));
+            Ok(())
+        }
+
+        /// Rent a token for `duration` blocks, paying the owner upfront. The token is frozen
+        /// with its owner until the rental ends.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn rent(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            duration: T::BlockNumber) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let terms = RentalOffers::<T>::get(collection_id, token_id).ok_or(Error::<T>::RentalNotFound)?;
+
+            ensure!(token.owner != who, Error::<T>::CannotBuyOwnToken);
+            ensure!(!TokenUsers::<T>::contains_key(collection_id, token_id), Error::<T>::AlreadyRented);
+            ensure!(!duration.is_zero() && duration <= terms.max_duration, Error::<T>::InvalidRentalDuration);
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+
+            let blocks = BalanceOf::<T>::saturated_from(duration.saturated_into::<u128>());
+            let cost = terms.price_per_block.saturating_mul(blocks);
+            T::Currency::transfer(&who, &token.owner, cost, ExistenceRequirement::KeepAlive)?;
+
+            let expires = frame_system::Pallet::<T>::block_number() + duration;
+            Self::remove_listing(collection_id, token_id);
+            Self::set_frozen(collection_id, token_id, true);
+            TokenUsers::<T>::insert(collection_id, token_id, (who.clone(), expires));
+            RentalEndings::<T>::insert(expires, (collection_id, token_id), ());
+            Self::deposit_event(RawEvent::TokenRented(collection_id, token_id, who, expires));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn create_auction(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            reserve_price: BalanceOf<T>,
+            min_increment: BalanceOf<T>,
+            end: T::BlockNumber) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            Self::ensure_transferable(collection_id)?;
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+            ensure!(end > frame_system::Pallet::<T>::block_number(), Error::<T>::InvalidAuctionEnd);
+
+            Self::remove_listing(collection_id, token_id);
+            Self::set_frozen(collection_id, token_id, true);
+            Self::set_sale_pending(collection_id, true);
+            Auctions::<T>::insert(collection_id, token_id, EnglishAuction {
+                seller: who,
+                reserve_price,
+                min_increment,
+                end,
+                high_bid: None,
+            });
+            AuctionEndings::<T>::insert(end, (collection_id, token_id), ());
+            Self::deposit_event(RawEvent::AuctionCreated(collection_id, token_id, reserve_price, end));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn bid(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut auction = Auctions::<T>::get(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(now < auction.end, Error::<T>::AuctionEnded);
+            ensure!(auction.seller != who, Error::<T>::CannotBuyOwnToken);
+
+            let minimum = match auction.high_bid {
+                Some((_, current)) => current.saturating_add(auction.min_increment),
+                None => auction.reserve_price,
+            };
+            ensure!(amount >= minimum, Error::<T>::BidTooLow);
+
+            T::Currency::reserve(&who, amount)?;
+            if let Some((previous, previous_amount)) = auction.high_bid.take() {
+                T::Currency::unreserve(&previous, previous_amount);
+            }
+            auction.high_bid = Some((who.clone(), amount));
+
+            // anti-sniping: a late bid keeps the auction open for another full extension period
+            let extend_period = T::AuctionExtendPeriod::get();
+            if auction.end.saturating_sub(now) < extend_period {
+                let new_end = now.saturating_add(extend_period);
+                AuctionEndings::<T>::remove(auction.end, (collection_id, token_id));
+                AuctionEndings::<T>::insert(new_end, (collection_id, token_id), ());
+                auction.end = new_end;
+                Self::deposit_event(RawEvent::AuctionExtended(collection_id, token_id, new_end));
+            }
+
+            Auctions::<T>::insert(collection_id, token_id, auction);
+            Self::deposit_event(RawEvent::BidPlaced(collection_id, token_id, who, amount));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_auction(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let auction = Auctions::<T>::get(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+            ensure!(auction.seller == who, Error::<T>::NotTokenOwner);
+            ensure!(auction.high_bid.is_none(), Error::<T>::AuctionHasBids);
+
+            Auctions::<T>::remove(collection_id, token_id);
+            AuctionEndings::<T>::remove(auction.end, (collection_id, token_id));
+            Self::set_frozen(collection_id, token_id, false);
+            Self::set_sale_pending(collection_id, false);
+            Self::deposit_event(RawEvent::AuctionCanceled(collection_id, token_id));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn create_dutch_auction(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            start_price: BalanceOf<T>,
+            floor_price: BalanceOf<T>,
+            decay_period: T::BlockNumber,
+            end: T::BlockNumber) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            Self::ensure_transferable(collection_id)?;
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+            ensure!(start_price >= floor_price, Error::<T>::InvalidPriceRange);
+            ensure!(end > now && !decay_period.is_zero(), Error::<T>::InvalidAuctionEnd);
+
+            Self::remove_listing(collection_id, token_id);
+            Self::set_frozen(collection_id, token_id, true);
+            Self::set_sale_pending(collection_id, true);
+            DutchAuctions::<T>::insert(collection_id, token_id, DutchAuction {
+                seller: who,
+                start_price,
+                floor_price,
+                start: now,
+                decay_period,
+                end,
+            });
+            DutchAuctionEndings::<T>::insert(end, (collection_id, token_id), ());
+            Self::deposit_event(RawEvent::DutchAuctionStarted(collection_id, token_id, start_price, floor_price, end));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn buy(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let auction = DutchAuctions::<T>::get(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(now < auction.end, Error::<T>::AuctionEnded);
+            ensure!(auction.seller != who, Error::<T>::CannotBuyOwnToken);
+
+            let price = Self::dutch_auction_price(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+            ensure!(T::Currency::free_balance(&who) >= price, Error::<T>::LowBalance);
+
+            DutchAuctions::<T>::remove(collection_id, token_id);
+            DutchAuctionEndings::<T>::remove(auction.end, (collection_id, token_id));
+            Self::set_frozen(collection_id, token_id, false);
+            Self::set_sale_pending(collection_id, false);
+
+            Self::pay_for_token(collection_id, token_id, T::NativeCurrencyId::get(), &who, &auction.seller, price, false)?;
+            Self::transfer_token(&auction.seller, &who, collection_id, token_id)?;
+            Self::deposit_event(RawEvent::DutchAuctionPurchased(collection_id, token_id, who, price));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_dutch_auction(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let auction = DutchAuctions::<T>::get(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+            ensure!(auction.seller == who, Error::<T>::NotTokenOwner);
+
+            DutchAuctions::<T>::remove(collection_id, token_id);
+            DutchAuctionEndings::<T>::remove(auction.end, (collection_id, token_id));
+            Self::set_frozen(collection_id, token_id, false);
+            Self::set_sale_pending(collection_id, false);
+            Self::deposit_event(RawEvent::AuctionCanceled(collection_id, token_id));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn create_sealed_auction(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            reserve_price: BalanceOf<T>,
+            commit_end: T::BlockNumber,
+            reveal_end: T::BlockNumber) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            Self::ensure_transferable(collection_id)?;
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+            ensure!(commit_end > now && reveal_end > commit_end, Error::<T>::InvalidAuctionEnd);
+
+            Self::remove_listing(collection_id, token_id);
+            Self::set_frozen(collection_id, token_id, true);
+            Self::set_sale_pending(collection_id, true);
+            SealedAuctions::<T>::insert(collection_id, token_id, SealedAuction {
+                seller: who,
+                reserve_price,
+                commit_end,
+                reveal_end,
+                bid_count: 0,
+                best: None,
+            });
+            SealedAuctionEndings::<T>::insert(reveal_end, (collection_id, token_id), ());
+            Self::deposit_event(RawEvent::SealedAuctionCreated(collection_id, token_id, commit_end, reveal_end));
+            Ok(())
+        }
+
+        /// Commit to a bid of `hash(bidder, amount, salt)`. Including the bidder keeps others from
+        /// copying the commitment. The deposit must cover the amount that will be revealed.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn commit_bid(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            commitment: T::Hash,
+            deposit: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut auction = SealedAuctions::<T>::get(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+            ensure!(frame_system::Pallet::<T>::block_number() < auction.commit_end, Error::<T>::NotInCommitPhase);
+            ensure!(auction.seller != who, Error::<T>::CannotBuyOwnToken);
+            ensure!(!SealedBids::<T>::contains_key((collection_id, token_id), &who), Error::<T>::BidAlreadyCommitted);
+            ensure!(auction.bid_count < T::MaxSealedBids::get(), Error::<T>::TooManyBids);
+
+            T::Currency::reserve(&who, deposit)?;
+            SealedBids::<T>::insert((collection_id, token_id), who.clone(), SealedBid {
+                commitment,
+                deposit,
+                revealed: None,
+            });
+            auction.bid_count += 1;
+            SealedAuctions::<T>::insert(collection_id, token_id, auction);
+            Self::deposit_event(RawEvent::BidCommitted(collection_id, token_id, who));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn reveal_bid(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            amount: BalanceOf<T>,
+            salt: T::Hash) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let mut auction = SealedAuctions::<T>::get(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+            let mut bid = SealedBids::<T>::get((collection_id, token_id), &who).ok_or(Error::<T>::BidNotFound)?;
+            let now = frame_system::Pallet::<T>::block_number();
+
+            ensure!(now >= auction.commit_end && now < auction.reveal_end, Error::<T>::NotInRevealPhase);
+            ensure!(bid.revealed.is_none(), Error::<T>::InvalidReveal);
+            ensure!(T::Hashing::hash_of(&(&who, amount, salt)) == bid.commitment, Error::<T>::InvalidReveal);
+            ensure!(amount <= bid.deposit, Error::<T>::InvalidReveal);
+
+            bid.revealed = Some(amount);
+            SealedBids::<T>::insert((collection_id, token_id), who.clone(), bid);
+
+            // ties go to whoever revealed first
+            let beats_best = match auction.best {
+                Some((_, best)) => amount > best,
+                None => amount >= auction.reserve_price,
+            };
+            if beats_best {
+                auction.best = Some((who.clone(), amount));
+                SealedAuctions::<T>::insert(collection_id, token_id, auction);
+            }
+            Self::deposit_event(RawEvent::BidRevealed(collection_id, token_id, who, amount));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_sealed_auction(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let auction = SealedAuctions::<T>::get(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+
+            ensure!(auction.seller == who, Error::<T>::NotTokenOwner);
+            ensure!(auction.bid_count == 0, Error::<T>::AuctionHasBids);
+
+            SealedAuctions::<T>::remove(collection_id, token_id);
+            SealedAuctionEndings::<T>::remove(auction.reveal_end, (collection_id, token_id));
+            Self::set_frozen(collection_id, token_id, false);
+            Self::set_sale_pending(collection_id, false);
+            Self::deposit_event(RawEvent::AuctionCanceled(collection_id, token_id));
+            Ok(())
+        }
+
+        /// Settle an auction, escrow or missed installment plan that is past its deadline. This
+        /// retries settlements that failed in `on_initialize` or are still queued behind others.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn settle(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            ensure_signed(origin)?;
+            let now = frame_system::Pallet::<T>::block_number();
+            let key = (collection_id, token_id);
+
+            if let Some(auction) = Auctions::<T>::get(collection_id, token_id) {
+                ensure!(auction.end <= now, Error::<T>::NothingToSettle);
+                AuctionEndings::<T>::remove(auction.end, key);
+                Self::settle_auction(collection_id, token_id)
+            } else if let Some(auction) = SealedAuctions::<T>::get(collection_id, token_id) {
+                ensure!(auction.reveal_end <= now, Error::<T>::NothingToSettle);
+                SealedAuctionEndings::<T>::remove(auction.reveal_end, key);
+                Self::settle_sealed_auction(collection_id, token_id)
+            } else if let Some(escrow) = Escrows::<T>::get(collection_id, token_id) {
+                ensure!(!escrow.disputed && escrow.release_at <= now, Error::<T>::NothingToSettle);
+                EscrowReleases::<T>::remove(escrow.release_at, key);
+                Self::release_escrow(collection_id, token_id)
+            } else if let Some(due) = InstallmentPlans::<T>::get(collection_id, token_id).and_then(|plan| plan.next_due) {
+                ensure!(due <= now, Error::<T>::NothingToSettle);
+                InstallmentDeadlines::<T>::remove(due, key);
+                Self::default_installment_plan(collection_id, token_id)
+            } else {
+                fail!(Error::<T>::NothingToSettle)
+            }
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn transfer(origin,
+                collection_id: T::ClassId,
+                token_id: T::TokenId,
+                recipient: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            Self::ensure_owner_or_approved(&who, collection_id, token_id, &token.owner)?;
+
+            let info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
+                display_flag: false,
+                report: ReportReason::None,
+                frozen: false,
+                display_for_sale: false
+            });
+
+            ensure!(info.frozen == false, Error::<T>::TokenFrozen);
+
+            Self::transfer_token(&token.owner, &recipient, collection_id, token_id)?;
+            Self::deposit_event(RawEvent::TokenTransferred(collection_id, token_id, recipient));
+            Ok(())
+        }
+
+        /// Make an offer on a token. Offers at or above the owner's auto-accept price are
+        /// executed immediately.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn create_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            price: BalanceOf<T>,
+            currency_id: CurrencyIdOf<T>,
+            expires: Option<T::BlockNumber>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner != who, Error::<T>::CannotBuyOwnToken);
+            Self::ensure_transferable(collection_id)?;
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            ensure!(!Offers::<T>::contains_key((collection_id, token_id), &who), Error::<T>::OfferAlreadyExists);
+            if let Some(expires) = expires {
+                ensure!(expires > frame_system::Pallet::<T>::block_number(), Error::<T>::InvalidExpiry);
+            }
+            ensure!(Self::accepts_currency(collection_id, token_id, currency_id), Error::<T>::CurrencyNotAccepted);
+            ensure!(
+                OfferCount::<T>::get(collection_id, token_id) < T::MaxOffersPerToken::get(),
+                Error::<T>::TooManyOffers
+            );
+            ensure!(
+                (BidderOffers::<T>::decode_len(&who).unwrap_or(0) as u32) < T::MaxOffersPerAccount::get(),
+                Error::<T>::TooManyAccountOffers
+            );
+            Self::ensure_above_minimum(collection_id, token_id, currency_id, price)?;
+
+            Self::reserve_funds(currency_id, &who, price)?;
+            let offer = Offer {
+                amount: price,
+                currency_id,
+                expires,
+                counter: None,
+                turn: Party::Owner,
+                rounds: sp_std::vec![(Party::Buyer, price)],
+            };
+            Self::insert_offer(collection_id, token_id, &who, offer.clone());
+            Self::deposit_event(RawEvent::OfferCreated(collection_id, token_id, price, currency_id, who.clone()));
+            Self::try_auto_accept(collection_id, token_id, &token.owner, &who, offer)
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn accept_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            buyer_address: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            Self::ensure_owner_or_approved(&who, collection_id, token_id, &token.owner)?;
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+
+            let info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
+                display_flag: false,
+                report: ReportReason::None,
+                frozen: false,
+                display_for_sale: false
+            });
+
+            ensure!(info.frozen == false, Error::<T>::TokenFrozen);
+
+            if let Some(offer) = Offers::<T>::get((collection_id, token_id), buyer_address.clone()){
+                ensure!(!Self::is_expired(&offer), Error::<T>::ExpiredOffer);
+                Self::execute_offer(collection_id, token_id, &token.owner, &buyer_address, offer)
+            } else {
+                fail!(Error::<T>::OfferNotFound);
+            }
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            if let Some(offer) = Self::remove_offer(collection_id, token_id, &who) {
+                Self::unreserve_funds(offer.currency_id, &who, offer.amount);
+                Self::deposit_event(RawEvent::OfferCanceled(collection_id, token_id, token.owner, who));
+                Ok(())
+            } else {
+                fail!(Error::<T>::OfferNotFound);
+            }
+        }
+
+        /// Change the amount of an open offer. Only the difference is reserved or released. The
+        /// new amount is recorded as a round and replaces any counter-price, so it is the owner's
+        /// turn to respond.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn update_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            price: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let mut offer = Offers::<T>::get((collection_id, token_id), &who).ok_or(Error::<T>::OfferNotFound)?;
+            ensure!(!Self::is_expired(&offer), Error::<T>::ExpiredOffer);
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            Self::ensure_above_minimum(collection_id, token_id, offer.currency_id, price)?;
+            Self::push_round(&mut offer, Party::Buyer, price)?;
+
+            if price > offer.amount {
+                Self::reserve_funds(offer.currency_id, &who, price - offer.amount)?;
+            } else {
+                Self::unreserve_funds(offer.currency_id, &who, offer.amount - price);
+            }
+            offer.amount = price;
+            offer.counter = None;
+            Offers::<T>::insert((collection_id, token_id), &who, offer.clone());
+            Self::deposit_event(RawEvent::OfferUpdated(collection_id, token_id, who.clone(), price));
+            Self::try_auto_accept(collection_id, token_id, &token.owner, &who, offer)
+        }
+
+        /// Withdraw every open offer made by the caller and release the reserved funds.
+        #[weight = T::DbWeight::get().reads_writes(
+            1 + 2 * T::MaxOffersPerAccount::get() as Weight,
+            1 + 4 * T::MaxOffersPerAccount::get() as Weight,
+        )]
+        pub fn cancel_all_offers(origin) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let tokens = BidderOffers::<T>::get(&who);
+            ensure!(!tokens.is_empty(), Error::<T>::OfferNotFound);
+
+            for (collection_id, token_id) in tokens.iter() {
+                if let Some(offer) = Self::remove_offer(*collection_id, *token_id, &who) {
+                    Self::unreserve_funds(offer.currency_id, &who, offer.amount);
+                }
+            }
+            Self::deposit_event(RawEvent::AllOffersCanceled(who, tokens.len() as u32));
+            Ok(())
+        }
+
+        /// Set a minimum offer and an auto-accept price for a token. Passing `None` for both
+        /// clears the settings. The settings are dropped when the token changes hands.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_sale_settings(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            currency_id: CurrencyIdOf<T>,
+            min_offer: Option<BalanceOf<T>>,
+            auto_accept: Option<BalanceOf<T>>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+
+            if min_offer.is_none() && auto_accept.is_none() {
+                TokenSaleSettings::<T>::remove(collection_id, token_id);
+                Self::deposit_event(RawEvent::SaleSettingsCleared(collection_id, token_id));
+                return Ok(());
+            }
+            if let (Some(min_offer), Some(auto_accept)) = (min_offer, auto_accept) {
+                ensure!(auto_accept >= min_offer, Error::<T>::InvalidPriceRange);
+            }
+            ensure!(Self::accepts_currency(collection_id, token_id, currency_id), Error::<T>::CurrencyNotAccepted);
+
+            TokenSaleSettings::<T>::insert(collection_id, token_id, SaleSettings { currency_id, min_offer, auto_accept });
+            Self::deposit_event(RawEvent::SaleSettingsSet(collection_id, token_id, currency_id, min_offer, auto_accept));
+            Ok(())
+        }
+
+        /// Restrict the currencies offers and listings for a token may use. An empty list lifts
+        /// the restriction.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn set_accepted_currencies(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            currencies: Vec<CurrencyIdOf<T>>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            ensure!(currencies.len() as u32 <= T::MaxAcceptedCurrencies::get(), Error::<T>::TooManyCurrencies);
+
+            if currencies.is_empty() {
+                AcceptedCurrencies::<T>::remove(collection_id, token_id);
+            } else {
+                AcceptedCurrencies::<T>::insert(collection_id, token_id, currencies.clone());
+            }
+            Self::deposit_event(RawEvent::AcceptedCurrenciesSet(collection_id, token_id, currencies));
+            Ok(())
+        }
+
+        /// Answer `buyer_address`'s offer with a higher asking price.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn counter_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            buyer_address: T::AccountId,
+            price: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let mut offer = Offers::<T>::get((collection_id, token_id), &buyer_address).ok_or(Error::<T>::OfferNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            ensure!(!Self::is_expired(&offer), Error::<T>::ExpiredOffer);
+            ensure!(offer.turn == Party::Owner, Error::<T>::NotYourTurn);
+            ensure!(price > offer.amount, Error::<T>::BidTooLow);
+
+            Self::push_round(&mut offer, Party::Owner, price)?;
+            offer.counter = Some(price);
+            Offers::<T>::insert((collection_id, token_id), &buyer_address, offer);
+            Self::deposit_event(RawEvent::OfferCountered(collection_id, token_id, buyer_address, price));
+            Ok(())
+        }
+
+        /// Respond to a counter-offer with a higher price of the buyer's own. A price at or
+        /// above the owner's auto-accept price is executed immediately.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn raise_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            price: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let mut offer = Offers::<T>::get((collection_id, token_id), &who).ok_or(Error::<T>::OfferNotFound)?;
+
+            ensure!(!Self::is_expired(&offer), Error::<T>::ExpiredOffer);
+            ensure!(offer.turn == Party::Buyer, Error::<T>::NotYourTurn);
+            ensure!(price > offer.amount, Error::<T>::BidTooLow);
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+
+            Self::push_round(&mut offer, Party::Buyer, price)?;
+            Self::reserve_funds(offer.currency_id, &who, price.saturating_sub(offer.amount))?;
+            offer.amount = price;
+            Offers::<T>::insert((collection_id, token_id), &who, offer.clone());
+            Self::deposit_event(RawEvent::OfferRaised(collection_id, token_id, who.clone(), price));
+            Self::try_auto_accept(collection_id, token_id, &token.owner, &who, offer)
+        }
+
+        /// Buy the token at the owner's latest counter-price.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn accept_counter_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let offer = Offers::<T>::get((collection_id, token_id), &who).ok_or(Error::<T>::OfferNotFound)?;
+
+            ensure!(!Self::is_expired(&offer), Error::<T>::ExpiredOffer);
+            ensure!(offer.turn == Party::Buyer, Error::<T>::NotYourTurn);
+            let price = offer.counter.ok_or(Error::<T>::NoCounterOffer)?;
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+
+            if price > offer.amount {
+                Self::reserve_funds(offer.currency_id, &who, price - offer.amount)?;
+            } else {
+                Self::unreserve_funds(offer.currency_id, &who, offer.amount - price);
+            }
+            Self::remove_offer(collection_id, token_id, &who);
+            Self::pay_for_token(collection_id, token_id, offer.currency_id, &who, &token.owner, price, true)?;
+            Self::transfer_token(&token.owner, &who, collection_id, token_id)?;
+            Self::deposit_event(RawEvent::CounterOfferAccepted(collection_id, token_id, token.owner, who, price));
+            Ok(())
+        }
+
+        /// Offer `price` for each of up to `quantity` tokens from a collection. The full amount is
+        /// reserved up front and released one `price` at a time as the offer is filled.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn create_collection_offer(origin,
+            collection_id: T::ClassId,
+            price: BalanceOf<T>,
+            currency_id: CurrencyIdOf<T>,
+            quantity: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            ensure!(nft::Pallet::<T>::classes(collection_id).is_some(), Error::<T>::CollectionNotFound);
+            Self::ensure_transferable(collection_id)?;
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            ensure!(quantity > 0, Error::<T>::InvalidQuantity);
+            ensure!(!CollectionOffers::<T>::contains_key(collection_id, &who), Error::<T>::OfferAlreadyExists);
+
+            Self::reserve_funds(currency_id, &who, price.saturating_mul(quantity.into()))?;
+            CollectionOffers::<T>::insert(collection_id, who.clone(), CollectionOffer { price, currency_id, quantity });
+            Self::deposit_event(RawEvent::CollectionOfferCreated(collection_id, who, price, quantity));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_collection_offer(origin, collection_id: T::ClassId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let offer = CollectionOffers::<T>::take(collection_id, &who).ok_or(Error::<T>::OfferNotFound)?;
+
+            Self::unreserve_funds(offer.currency_id, &who, offer.price.saturating_mul(offer.quantity.into()));
+            Self::deposit_event(RawEvent::CollectionOfferCanceled(collection_id, who));
+            Ok(())
+        }
+
+        /// Sell one of the caller's tokens into `buyer_address`'s collection offer.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn accept_collection_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            buyer_address: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            let mut offer = CollectionOffers::<T>::get(collection_id, &buyer_address).ok_or(Error::<T>::OfferNotFound)?;
+
+            ensure!(token.owner == who, Error::<T>::NotTokenOwner);
+            ensure!(buyer_address != who, Error::<T>::CannotBuyOwnToken);
+            Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+
+            Self::pay_for_token(collection_id, token_id, offer.currency_id, &buyer_address, &who, offer.price, true)?;
+            Self::transfer_token(&who, &buyer_address, collection_id, token_id)?;
+
+            offer.quantity -= 1;
+            if offer.quantity == 0 {
+                CollectionOffers::<T>::remove(collection_id, &buyer_address);
+            } else {
+                CollectionOffers::<T>::insert(collection_id, &buyer_address, offer.clone());
+            }
+            Self::deposit_event(RawEvent::CollectionOfferFilled(collection_id, token_id, who, buyer_address, offer.price));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn list_bundle(origin,
+            items: Vec<(T::ClassId, T::TokenId)>,
+            price: BalanceOf<T>,
+            currency_id: CurrencyIdOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_valid_bundle(&items)?;
+            Self::ensure_bundle_sellable(&items, &who)?;
+            for &(collection_id, token_id) in items.iter() {
+                ensure!(Self::accepts_currency(collection_id, token_id, currency_id), Error::<T>::CurrencyNotAccepted);
+                ensure!(!BundledTokens::<T>::contains_key(collection_id, token_id), Error::<T>::AlreadyBundled);
+            }
+
+            let bundle_id = Self::take_bundle_id();
+            for &(collection_id, token_id) in items.iter() {
+                BundledTokens::<T>::insert(collection_id, token_id, bundle_id);
+            }
+            BundleListings::<T>::insert(bundle_id, Bundle { account: who.clone(), items, price, currency_id });
+            Self::deposit_event(RawEvent::BundleListed(bundle_id, who, price));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_bundle_listing(origin, bundle_id: BundleId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let bundle = BundleListings::<T>::get(bundle_id).ok_or(Error::<T>::BundleNotFound)?;
+
+            ensure!(bundle.account == who, Error::<T>::NotTokenOwner);
+
+            Self::remove_bundle_listing(bundle_id);
+            Self::deposit_event(RawEvent::BundleListingCanceled(bundle_id));
+            Ok(())
+        }
+
+        /// Buy every token in a bundle listing. Either all tokens change hands or none do.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn buy_bundle(origin, bundle_id: BundleId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let bundle = BundleListings::<T>::get(bundle_id).ok_or(Error::<T>::BundleNotFound)?;
+
+            ensure!(bundle.account != who, Error::<T>::CannotBuyOwnToken);
+            Self::ensure_bundle_sellable(&bundle.items, &bundle.account)?;
+
+            Self::remove_bundle_listing(bundle_id);
+            Self::pay_for_bundle(&bundle, &who, &bundle.account, false)?;
+            for &(collection_id, token_id) in bundle.items.iter() {
+                Self::transfer_token(&bundle.account, &who, collection_id, token_id)?;
+            }
+            Self::deposit_event(RawEvent::BundleSold(bundle_id, bundle.account, who, bundle.price));
+            Ok(())
+        }
+
+        /// Offer one price for a set of tokens that currently share an owner.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn create_bundle_offer(origin,
+            items: Vec<(T::ClassId, T::TokenId)>,
+            price: BalanceOf<T>,
+            currency_id: CurrencyIdOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            Self::ensure_valid_bundle(&items)?;
+            let (first_collection, first_token) = items[0];
+            let owner = nft::Pallet::<T>::tokens(first_collection, first_token).ok_or(Error::<T>::TokenNotFound)?.owner;
+            ensure!(owner != who, Error::<T>::CannotBuyOwnToken);
+            for &(collection_id, token_id) in items.iter() {
+                let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+                ensure!(token.owner == owner, Error::<T>::InvalidBundle);
+                ensure!(Self::accepts_currency(collection_id, token_id, currency_id), Error::<T>::CurrencyNotAccepted);
+                Self::ensure_transferable(collection_id)?;
+                Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            }
+
+            Self::reserve_funds(currency_id, &who, price)?;
+            let bundle_id = Self::take_bundle_id();
+            BundleOffers::<T>::insert(bundle_id, Bundle { account: who.clone(), items, price, currency_id });
+            Self::deposit_event(RawEvent::BundleOfferCreated(bundle_id, who, price));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn cancel_bundle_offer(origin, bundle_id: BundleId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let bundle = BundleOffers::<T>::get(bundle_id).ok_or(Error::<T>::BundleNotFound)?;
+
+            ensure!(bundle.account == who, Error::<T>::OfferNotFound);
+
+            BundleOffers::<T>::remove(bundle_id);
+            Self::unreserve_funds(bundle.currency_id, &who, bundle.price);
+            Self::deposit_event(RawEvent::BundleOfferCanceled(bundle_id));
+            Ok(())
+        }
+
+        /// Sell every token in a bundle offer. Either all tokens change hands or none do.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn accept_bundle_offer(origin, bundle_id: BundleId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let bundle = BundleOffers::<T>::get(bundle_id).ok_or(Error::<T>::BundleNotFound)?;
+
+            Self::ensure_bundle_sellable(&bundle.items, &who)?;
+            for &(collection_id, _) in bundle.items.iter() {
+                Self::ensure_setting(collection_id, CollectionSettings::OFFERS_ENABLED, Error::<T>::OffersDisabled)?;
+            }
+
+            BundleOffers::<T>::remove(bundle_id);
+            Self::pay_for_bundle(&bundle, &bundle.account, &who, true)?;
+            for &(collection_id, token_id) in bundle.items.iter() {
+                Self::transfer_token(&who, &bundle.account, collection_id, token_id)?;
+            }
+            Self::deposit_event(RawEvent::BundleOfferAccepted(bundle_id, who, bundle.account, bundle.price));
+            Ok(())
+        }
+
+        /// Refund an expired offer that was not cleaned up automatically. The caller receives
+        /// `OfferReapReward`, in the offer's currency, out of the offer's reserve.
+        #[weight = T::BlockWeights::get().max_block / 100]
+        #[transactional]
+        pub fn reap_expired_offer(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            buyer: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let offer = Offers::<T>::get((collection_id, token_id), &buyer).ok_or(Error::<T>::OfferNotFound)?;
+
+            ensure!(Self::is_expired(&offer), Error::<T>::OfferNotExpired);
+
+            Self::remove_offer(collection_id, token_id, &buyer);
+            let reward = offer.amount.min(T::OfferReapReward::get());
+            Self::pay(offer.currency_id, &buyer, &who, reward, true)?;
+            Self::unreserve_funds(offer.currency_id, &buyer, offer.amount.saturating_sub(reward));
+            Self::deposit_event(RawEvent::OfferExpired(collection_id, token_id, buyer));
+            Ok(())
+        }
+
+        #[weight = T::BlockWeights::get().max_block / 100]
+        pub fn report(origin,
+            collection_id: T::ClassId,
+            token_id: T::TokenId,
+            reason: ReportReason) -> DispatchResult {
+            ensure_signed(origin)?;
+            ensure!(nft::Pallet::<T>::tokens(collection_id, token_id).is_some(), Error::<T>::TokenNotFound);
+
+            let mut info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
+                display_flag: false,
+                report: ReportReason::None,
+                frozen: false,
+                display_for_sale: false
+            });
+
+            info.report = reason.clone();
+            TokenExtendedInfo::<T>::insert(collection_id, token_id, info);
+            Self::deposit_event(RawEvent::ReportReceived(collection_id, token_id, reason));
             Ok(())
         }
 
@@ -363,8 +2390,15 @@ decl_module! {
                 token_id: T::TokenId) -> DispatchResult {
             let who = ensure_signed(origin)?;
             let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
 
-            ensure!(Curator::<T>::get() == who || collection.owner == who, Error::<T>::NotCollectionOwnerOrCurator);
+            ensure!(
+                Curator::<T>::get() == who
+                    || collection.owner == who
+                    || (collection.data.holder_burnable && token.owner == who),
+                Error::<T>::NotCollectionOwnerOrCurator
+            );
+            Self::ensure_setting(collection_id, CollectionSettings::BURNABLE, Error::<T>::BurningDisabled)?;
 
             let info = TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
                 display_flag: false,
@@ -377,13 +2411,625 @@ decl_module! {
 
             // doesn't make sense - the burn could be by a different person than the lock.
             //T::Currency::remove_lock(PALLET_ID, &who);
-            nft::Pallet::<T>::burn(&who, (collection_id, token_id))?;
+            nft::Pallet::<T>::burn(&token.owner, (collection_id, token_id))?;
+            Self::remove_listing(collection_id, token_id);
+            Self::unbundle(collection_id, token_id);
+            Self::refund_offers(collection_id, token_id);
+            Self::remove_unstarted_plan(collection_id, token_id);
+            TokenExtendedInfo::<T>::remove(collection_id, token_id);
+            TokenRoyalties::<T>::remove(collection_id, token_id);
+            AcceptedCurrencies::<T>::remove(collection_id, token_id);
+            TokenSaleSettings::<T>::remove(collection_id, token_id);
+            RentalOffers::<T>::remove(collection_id, token_id);
+            TokenApprovals::<T>::remove(collection_id, token_id);
             Self::deposit_event(RawEvent::TokenBurned(collection_id, token_id));
             Ok(())
         }
     }
 }
 
+impl<T: Config> Module<T> {
+    /// Open offers made by an account, with the currency and amount reserved for each.
+    pub fn account_offers(who: &T::AccountId) -> Vec<(T::ClassId, T::TokenId, CurrencyIdOf<T>, BalanceOf<T>)> {
+        BidderOffers::<T>::get(who)
+            .into_iter()
+            .filter_map(|(collection_id, token_id)| {
+                Offers::<T>::get((collection_id, token_id), who)
+                    .map(|offer| (collection_id, token_id, offer.currency_id, offer.amount))
+            })
+            .collect()
+    }
+
+    /// The price a buyer would pay for a Dutch auction at the current block.
+    pub fn dutch_auction_price(collection_id: T::ClassId, token_id: T::TokenId) -> Option<BalanceOf<T>> {
+        let auction = DutchAuctions::<T>::get(collection_id, token_id)?;
+        let elapsed = frame_system::Pallet::<T>::block_number().saturating_sub(auction.start);
+        if elapsed >= auction.decay_period {
+            return Some(auction.floor_price);
+        }
+
+        let decayed = Perbill::from_rational_approximation(elapsed, auction.decay_period)
+            * auction.start_price.saturating_sub(auction.floor_price);
+        Some(auction.start_price.saturating_sub(decayed))
+    }
+
+    fn extended_info(collection_id: T::ClassId, token_id: T::TokenId) -> ExtendedInfo {
+        TokenExtendedInfo::<T>::get(collection_id, token_id).unwrap_or_else(|| ExtendedInfo {
+            display_flag: false,
+            report: ReportReason::None,
+            frozen: false,
+            display_for_sale: false
+        })
+    }
+
+    /// Royalty owed on sales of a token, preferring a token override over the collection default.
+    pub fn royalty_of(collection_id: T::ClassId, token_id: T::TokenId) -> Option<Royalty<T::AccountId>> {
+        TokenRoyalties::<T>::get(collection_id, token_id)
+            .or_else(|| CollectionRoyalties::<T>::get(collection_id))
+    }
+
+    pub fn marketplace_fee() -> Perbill {
+        MarketplaceFeeRate::get().unwrap_or_else(T::MarketplaceFee::get)
+    }
+
+    /// Pays `price` from `buyer` to `seller`. The marketplace fee is taken first and the royalty
+    /// share is routed to its beneficiary, both computed on the full price.
+    /// With `from_reserve` the payment comes out of the buyer's reserved balance.
+    fn pay_for_token(
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+        currency_id: CurrencyIdOf<T>,
+        buyer: &T::AccountId,
+        seller: &T::AccountId,
+        price: BalanceOf<T>,
+        from_reserve: bool,
+    ) -> DispatchResult {
+        let mut remaining = price;
+
+        let fee = Self::marketplace_fee() * price;
+        if !fee.is_zero() {
+            if currency_id == T::NativeCurrencyId::get() {
+                let imbalance = if from_reserve {
+                    T::Currency::slash_reserved(buyer, fee).0
+                } else {
+                    T::Currency::withdraw(buyer, fee, WithdrawReasons::TRANSFER, ExistenceRequirement::AllowDeath)?
+                };
+                T::FeeDestination::on_unbalanced(imbalance);
+            } else {
+                Self::pay(currency_id, buyer, &T::FeeCollector::get(), fee, from_reserve)?;
+            }
+            remaining = remaining.saturating_sub(fee);
+            Self::deposit_event(RawEvent::MarketplaceFeePaid(collection_id, token_id, fee));
+        }
+
+        if let Some(royalty) = Self::royalty_of(collection_id, token_id) {
+            // the cap is re-applied here in case `MaxRoyalty` was lowered after the royalty was set
+            let amount = royalty.rate.min(T::MaxRoyalty::get()) * price;
+            if royalty.beneficiary != *seller && !amount.is_zero() {
+                Self::pay(currency_id, buyer, &royalty.beneficiary, amount, from_reserve)?;
+                remaining = remaining.saturating_sub(amount);
+                Self::deposit_event(RawEvent::RoyaltyPaid(collection_id, token_id, royalty.beneficiary, amount));
+            }
+        }
+
+        Self::pay(currency_id, buyer, seller, remaining, from_reserve)
+    }
+
+    /// Pays for a bundle by splitting its price evenly across the items, so that fees and
+    /// royalties are worked out per token. Any rounding remainder goes with the last item.
+    fn pay_for_bundle(
+        bundle: &BundleOf<T>,
+        buyer: &T::AccountId,
+        seller: &T::AccountId,
+        from_reserve: bool,
+    ) -> DispatchResult {
+        let share = bundle.price / BalanceOf::<T>::from(bundle.items.len() as u32);
+        let mut remaining = bundle.price;
+
+        for (index, &(collection_id, token_id)) in bundle.items.iter().enumerate() {
+            let amount = if index + 1 == bundle.items.len() { remaining } else { share };
+            remaining = remaining.saturating_sub(amount);
+            Self::pay_for_token(collection_id, token_id, bundle.currency_id, buyer, seller, amount, from_reserve)?;
+        }
+        Ok(())
+    }
+
+    fn take_bundle_id() -> BundleId {
+        NextBundleId::mutate(|next| {
+            let id = *next;
+            *next = next.saturating_add(1);
+            id
+        })
+    }
+
+    fn remove_bundle_listing(bundle_id: BundleId) -> Option<BundleOf<T>> {
+        let bundle = BundleListings::<T>::take(bundle_id)?;
+        for &(collection_id, token_id) in bundle.items.iter() {
+            BundledTokens::<T>::remove(collection_id, token_id);
+        }
+        Some(bundle)
+    }
+
+    /// Cancels the bundle listing a token is part of. The listing was made by the token's
+    /// previous owner, so it must not come back to life if the token returns to them.
+    fn unbundle(collection_id: T::ClassId, token_id: T::TokenId) {
+        if let Some(bundle_id) = BundledTokens::<T>::get(collection_id, token_id) {
+            Self::remove_bundle_listing(bundle_id);
+            Self::deposit_event(RawEvent::BundleListingCanceled(bundle_id));
+        }
+    }
+
+    fn ensure_valid_bundle(items: &[(T::ClassId, T::TokenId)]) -> DispatchResult {
+        ensure!(!items.is_empty() && items.len() as u32 <= T::MaxBundleSize::get(), Error::<T>::InvalidBundle);
+        let has_duplicates = items.iter().enumerate().any(|(index, item)| items[..index].contains(item));
+        ensure!(!has_duplicates, Error::<T>::InvalidBundle);
+        Ok(())
+    }
+
+    fn ensure_bundle_sellable(items: &[(T::ClassId, T::TokenId)], seller: &T::AccountId) -> DispatchResult {
+        for &(collection_id, token_id) in items.iter() {
+            let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+            ensure!(token.owner == *seller, Error::<T>::NotTokenOwner);
+            ensure!(Self::extended_info(collection_id, token_id).frozen == false, Error::<T>::TokenFrozen);
+            Self::ensure_transferable(collection_id)?;
+        }
+        Ok(())
+    }
+
+    fn pay(
+        currency_id: CurrencyIdOf<T>,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: BalanceOf<T>,
+        from_reserve: bool,
+    ) -> DispatchResult {
+        if currency_id == T::NativeCurrencyId::get() {
+            if from_reserve {
+                T::Currency::repatriate_reserved(from, to, amount, BalanceStatus::Free)?;
+            } else {
+                T::Currency::transfer(from, to, amount, ExistenceRequirement::AllowDeath)?;
+            }
+        } else if from_reserve {
+            T::MultiCurrency::repatriate_reserved(currency_id, from, to, amount, BalanceStatus::Free)?;
+        } else {
+            T::MultiCurrency::transfer(currency_id, from, to, amount)?;
+        }
+        Ok(())
+    }
+
+    fn free_balance(currency_id: CurrencyIdOf<T>, who: &T::AccountId) -> BalanceOf<T> {
+        if currency_id == T::NativeCurrencyId::get() {
+            T::Currency::free_balance(who)
+        } else {
+            T::MultiCurrency::free_balance(currency_id, who)
+        }
+    }
+
+    fn minimum_balance(currency_id: CurrencyIdOf<T>) -> BalanceOf<T> {
+        if currency_id == T::NativeCurrencyId::get() {
+            T::Currency::minimum_balance()
+        } else {
+            T::MultiCurrency::minimum_balance(currency_id)
+        }
+    }
+
+    fn reserve_funds(currency_id: CurrencyIdOf<T>, who: &T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+        if currency_id == T::NativeCurrencyId::get() {
+            T::Currency::reserve(who, amount)
+        } else {
+            T::MultiCurrency::reserve(currency_id, who, amount)
+        }
+    }
+
+    fn unreserve_funds(currency_id: CurrencyIdOf<T>, who: &T::AccountId, amount: BalanceOf<T>) {
+        if currency_id == T::NativeCurrencyId::get() {
+            T::Currency::unreserve(who, amount);
+        } else {
+            T::MultiCurrency::unreserve(currency_id, who, amount);
+        }
+    }
+
+    fn accepts_currency(collection_id: T::ClassId, token_id: T::TokenId, currency_id: CurrencyIdOf<T>) -> bool {
+        AcceptedCurrencies::<T>::get(collection_id, token_id)
+            .map_or(true, |currencies| currencies.contains(&currency_id))
+    }
+
+    /// Offers in a currency other than the settings' one are refused while a minimum is set,
+    /// since they cannot be compared against it.
+    fn ensure_above_minimum(
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+        currency_id: CurrencyIdOf<T>,
+        price: BalanceOf<T>,
+    ) -> DispatchResult {
+        if let Some(settings) = TokenSaleSettings::<T>::get(collection_id, token_id) {
+            if let Some(min_offer) = settings.min_offer {
+                ensure!(settings.currency_id == currency_id, Error::<T>::CurrencyNotAccepted);
+                ensure!(price >= min_offer, Error::<T>::OfferBelowMinimum);
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes the offer if it meets the owner's auto-accept price and the token can be sold.
+    fn try_auto_accept(
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+        owner: &T::AccountId,
+        buyer: &T::AccountId,
+        offer: OfferOf<T>,
+    ) -> DispatchResult {
+        let accepts = TokenSaleSettings::<T>::get(collection_id, token_id).map_or(false, |settings| {
+            settings.currency_id == offer.currency_id
+                && settings.auto_accept.map_or(false, |auto_accept| offer.amount >= auto_accept)
+        });
+        if accepts && !Self::extended_info(collection_id, token_id).frozen {
+            Self::execute_offer(collection_id, token_id, owner, buyer, offer)?;
+        }
+        Ok(())
+    }
+
+    fn execute_offer(
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+        owner: &T::AccountId,
+        buyer: &T::AccountId,
+        offer: OfferOf<T>,
+    ) -> DispatchResult {
+        Self::pay_for_token(collection_id, token_id, offer.currency_id, buyer, owner, offer.amount, true)?;
+        Self::remove_offer(collection_id, token_id, buyer);
+        Self::transfer_token(owner, buyer, collection_id, token_id)?;
+        Self::deposit_event(RawEvent::OfferAccepted(collection_id, token_id, owner.clone(), buyer.clone()));
+        Ok(())
+    }
+
+    fn is_expired(offer: &OfferOf<T>) -> bool {
+        offer.expires.map_or(false, |expires| expires <= frame_system::Pallet::<T>::block_number())
+    }
+
+    fn push_round(offer: &mut OfferOf<T>, party: Party, price: BalanceOf<T>) -> DispatchResult {
+        ensure!((offer.rounds.len() as u32) < T::MaxNegotiationRounds::get(), Error::<T>::TooManyRounds);
+        offer.rounds.push((party, price));
+        offer.turn = match party {
+            Party::Buyer => Party::Owner,
+            Party::Owner => Party::Buyer,
+        };
+        Ok(())
+    }
+
+    fn insert_offer(collection_id: T::ClassId, token_id: T::TokenId, buyer: &T::AccountId, offer: OfferOf<T>) {
+        if let Some(expires) = offer.expires {
+            OfferExpiries::<T>::insert(expires, ((collection_id, token_id), buyer.clone()), ());
+        }
+        if !Offers::<T>::contains_key((collection_id, token_id), buyer) {
+            OfferCount::<T>::mutate(collection_id, token_id, |count| *count += 1);
+            BidderOffers::<T>::append(buyer, (collection_id, token_id));
+        }
+        Offers::<T>::insert((collection_id, token_id), buyer, offer);
+    }
+
+    /// Removes an offer and its expiry entry. Releasing the reserve is left to the caller.
+    fn remove_offer(collection_id: T::ClassId, token_id: T::TokenId, buyer: &T::AccountId) -> Option<OfferOf<T>> {
+        let offer = Offers::<T>::take((collection_id, token_id), buyer)?;
+        if let Some(expires) = offer.expires {
+            OfferExpiries::<T>::remove(expires, ((collection_id, token_id), buyer.clone()));
+        }
+        OfferCount::<T>::mutate_exists(collection_id, token_id, |count| {
+            *count = count.map(|c| c.saturating_sub(1)).filter(|c| *c > 0);
+        });
+        Self::unindex_offer(buyer, collection_id, token_id);
+        Some(offer)
+    }
+
+    fn unindex_offer(buyer: &T::AccountId, collection_id: T::ClassId, token_id: T::TokenId) {
+        BidderOffers::<T>::mutate_exists(buyer, |tokens| {
+            if let Some(list) = tokens {
+                list.retain(|key| *key != (collection_id, token_id));
+                if list.is_empty() {
+                    *tokens = None;
+                }
+            }
+        });
+    }
+
+    /// Refunds every open offer on a token. Offers are made to whoever owns the token, so they
+    /// cannot survive a change of owner.
+    fn refund_offers(collection_id: T::ClassId, token_id: T::TokenId) {
+        for (buyer, offer) in Offers::<T>::drain_prefix((collection_id, token_id)) {
+            if let Some(expires) = offer.expires {
+                OfferExpiries::<T>::remove(expires, ((collection_id, token_id), buyer.clone()));
+            }
+            Self::unreserve_funds(offer.currency_id, &buyer, offer.amount);
+            Self::unindex_offer(&buyer, collection_id, token_id);
+            Self::deposit_event(RawEvent::OfferRefunded(collection_id, token_id, buyer));
+        }
+        OfferCount::<T>::remove(collection_id, token_id);
+    }
+
+    fn set_sale_pending(collection_id: T::ClassId, pending: bool) {
+        PendingSales::<T>::mutate_exists(collection_id, |count| {
+            let current = count.unwrap_or(0);
+            let next = if pending { current.saturating_add(1) } else { current.saturating_sub(1) };
+            *count = Some(next).filter(|c| *c > 0);
+        });
+    }
+
+    fn set_frozen(collection_id: T::ClassId, token_id: T::TokenId, frozen: bool) {
+        let mut info = Self::extended_info(collection_id, token_id);
+        info.frozen = frozen;
+        TokenExtendedInfo::<T>::insert(collection_id, token_id, info);
+    }
+
+    /// Moves a token between accounts and drops any sale state tied to the previous owner.
+    fn transfer_token(
+        from: &T::AccountId,
+        to: &T::AccountId,
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+    ) -> DispatchResult {
+        Self::ensure_transferable(collection_id)?;
+        nft::Pallet::<T>::transfer(from, to, (collection_id, token_id))?;
+        Self::remove_listing(collection_id, token_id);
+        Self::unbundle(collection_id, token_id);
+        Self::refund_offers(collection_id, token_id);
+        Self::remove_unstarted_plan(collection_id, token_id);
+        TokenSaleSettings::<T>::remove(collection_id, token_id);
+        AcceptedCurrencies::<T>::remove(collection_id, token_id);
+        RentalOffers::<T>::remove(collection_id, token_id);
+        TokenApprovals::<T>::remove(collection_id, token_id);
+        Ok(())
+    }
+
+    /// Ends a plan whose deadline passed. The seller keeps the forfeited share of what was
+    /// paid, the rest is released back to the buyer and the token is unlocked.
+    #[transactional]
+    fn default_installment_plan(collection_id: T::ClassId, token_id: T::TokenId) -> DispatchResult {
+        let plan = InstallmentPlans::<T>::take(collection_id, token_id).ok_or(Error::<T>::PlanNotFound)?;
+        let forfeited = plan.forfeit * plan.paid;
+
+        T::Currency::repatriate_reserved(&plan.buyer, &plan.seller, forfeited, BalanceStatus::Free)?;
+        T::Currency::unreserve(&plan.buyer, plan.paid.saturating_sub(forfeited));
+        Self::set_frozen(collection_id, token_id, false);
+        Self::set_sale_pending(collection_id, false);
+        Self::deposit_event(RawEvent::InstallmentPlanDefaulted(collection_id, token_id, plan.buyer, forfeited));
+        Ok(())
+    }
+
+    /// Whether `operator` may currently act for `owner` on a token.
+    pub fn is_approved(
+        owner: &T::AccountId,
+        operator: &T::AccountId,
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+    ) -> bool {
+        let now = frame_system::Pallet::<T>::block_number();
+        let live = |expires: Option<T::BlockNumber>| expires.map_or(true, |expires| now < expires);
+
+        TokenApprovals::<T>::get(collection_id, token_id)
+            .map_or(false, |(approved, expires)| approved == *operator && live(expires))
+            || CollectionOperators::<T>::get((collection_id, owner.clone()), operator).map_or(false, live)
+    }
+
+    fn ensure_owner_or_approved(
+        who: &T::AccountId,
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+        owner: &T::AccountId,
+    ) -> DispatchResult {
+        ensure!(
+            owner == who || Self::is_approved(owner, who, collection_id, token_id),
+            Error::<T>::NotTokenOwner
+        );
+        Ok(())
+    }
+
+    /// Tokens in soulbound collections never change hands once minted.
+    fn ensure_transferable(collection_id: T::ClassId) -> DispatchResult {
+        let collection = nft::Pallet::<T>::classes(collection_id).ok_or(Error::<T>::CollectionNotFound)?;
+        ensure!(!collection.data.soulbound, Error::<T>::Soulbound);
+        Self::ensure_setting(collection_id, CollectionSettings::TRANSFERABLE, Error::<T>::TransfersDisabled)
+    }
+
+    /// Folds a proof into a root. Each pair is hashed in sorted order, so proofs don't need to
+    /// say which side a sibling sits on.
+    pub fn merkle_root(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+        proof.iter().fold(leaf, |node, sibling| {
+            let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+            let mut pair = [0u8; 64];
+            pair[..32].copy_from_slice(&left);
+            pair[32..].copy_from_slice(&right);
+            sp_io::hashing::blake2_256(&pair)
+        })
+    }
+
+    /// The cap counts every token ever minted, so burning a token doesn't make room for another.
+    fn ensure_below_max_supply(collection_id: T::ClassId) -> DispatchResult {
+        if let Some(max_supply) = MaxSupply::<T>::get(collection_id) {
+            ensure!(nft::NextTokenId::<T>::get(collection_id) < max_supply, Error::<T>::MaxSupplyReached);
+        }
+        Ok(())
+    }
+
+    fn ensure_setting(collection_id: T::ClassId, flag: u8, error: Error<T>) -> DispatchResult {
+        ensure!(Self::collection_settings(collection_id).allows(flag), error);
+        Ok(())
+    }
+
+    fn end_rental(collection_id: T::ClassId, token_id: T::TokenId) {
+        if let Some((user, _)) = TokenUsers::<T>::take(collection_id, token_id) {
+            Self::set_frozen(collection_id, token_id, false);
+            Self::deposit_event(RawEvent::RentalEnded(collection_id, token_id, user));
+        }
+    }
+
+    /// The account holding payments for sales that are still in escrow.
+    pub fn escrow_account() -> T::AccountId {
+        T::EscrowModuleId::get().into_account()
+    }
+
+    /// Pays an escrowed sale out to the seller, with the usual fee and royalty, and unfreezes
+    /// the token. The whole price leaves the escrow account in one transfer and the fee and
+    /// royalty are then taken from the seller, so the pooled account never drops below the
+    /// existential deposit part way through. A release that fails can be retried with `settle`.
+    #[transactional]
+    fn release_escrow(collection_id: T::ClassId, token_id: T::TokenId) -> DispatchResult {
+        let escrow = Escrows::<T>::take(collection_id, token_id).ok_or(Error::<T>::EscrowNotFound)?;
+        Self::pay(escrow.currency_id, &Self::escrow_account(), &escrow.seller, escrow.price, false)?;
+        Self::pay_for_token(
+            collection_id,
+            token_id,
+            escrow.currency_id,
+            &escrow.seller,
+            &escrow.seller,
+            escrow.price,
+            false,
+        )?;
+        Self::set_frozen(collection_id, token_id, false);
+        Self::set_sale_pending(collection_id, false);
+        Self::deposit_event(RawEvent::EscrowReleased(collection_id, token_id, escrow.seller, escrow.price));
+        Ok(())
+    }
+
+    #[transactional]
+    fn settle_auction(collection_id: T::ClassId, token_id: T::TokenId) -> DispatchResult {
+        let auction = Auctions::<T>::take(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+        Self::set_frozen(collection_id, token_id, false);
+        Self::set_sale_pending(collection_id, false);
+
+        if let Some((buyer, price)) = auction.high_bid {
+            Self::pay_for_token(collection_id, token_id, T::NativeCurrencyId::get(), &buyer, &auction.seller, price, true)?;
+            Self::transfer_token(&auction.seller, &buyer, collection_id, token_id)?;
+            Self::deposit_event(RawEvent::AuctionSettled(collection_id, token_id, buyer, price));
+        } else {
+            Self::deposit_event(RawEvent::AuctionEndedWithoutSale(collection_id, token_id));
+        }
+        Ok(())
+    }
+
+    /// Pays the seller from the winning deposit, refunds revealed losers and slashes part of
+    /// every deposit that was never revealed.
+    #[transactional]
+    fn settle_sealed_auction(collection_id: T::ClassId, token_id: T::TokenId) -> DispatchResult {
+        let auction = SealedAuctions::<T>::take(collection_id, token_id).ok_or(Error::<T>::AuctionNotFound)?;
+        Self::set_frozen(collection_id, token_id, false);
+        Self::set_sale_pending(collection_id, false);
+
+        for (bidder, bid) in SealedBids::<T>::drain_prefix((collection_id, token_id)) {
+            match bid.revealed {
+                Some(amount) if auction.best == Some((bidder.clone(), amount)) => {
+                    Self::pay_for_token(collection_id, token_id, T::NativeCurrencyId::get(), &bidder, &auction.seller, amount, true)?;
+                    T::Currency::unreserve(&bidder, bid.deposit.saturating_sub(amount));
+                }
+                Some(_) => {
+                    T::Currency::unreserve(&bidder, bid.deposit);
+                }
+                None => {
+                    let penalty = T::UnrevealedBidSlash::get() * bid.deposit;
+                    let _ = T::Currency::slash_reserved(&bidder, penalty);
+                    T::Currency::unreserve(&bidder, bid.deposit.saturating_sub(penalty));
+                    Self::deposit_event(RawEvent::BidDepositSlashed(collection_id, token_id, bidder, penalty));
+                }
+            }
+        }
+
+        if let Some((buyer, price)) = auction.best {
+            Self::transfer_token(&auction.seller, &buyer, collection_id, token_id)?;
+            Self::deposit_event(RawEvent::AuctionSettled(collection_id, token_id, buyer, price));
+        } else {
+            Self::deposit_event(RawEvent::AuctionEndedWithoutSale(collection_id, token_id));
+        }
+        Ok(())
+    }
+
+    /// Takes up to `MaxSettlementsPerBlock` entries due at or before `now` from a queue, oldest
+    /// block first. Whatever is left is recorded in `SettlementBacklog` for the next block.
+    fn due_settlements<Q, K, V>(queue: SettlementQueue, now: T::BlockNumber) -> Vec<(K, V)>
+    where
+        Q: IterableStorageDoubleMap<T::BlockNumber, K, V>,
+        K: FullCodec,
+        V: FullCodec,
+    {
+        let limit = T::MaxSettlementsPerBlock::get() as usize;
+        let mut at = SettlementBacklog::<T>::get(queue).unwrap_or(now);
+        let mut due = Vec::new();
+        let mut visited = 0;
+
+        while at <= now && due.len() < limit && visited < limit {
+            due.extend(Q::drain_prefix(at).take(limit - due.len()));
+            if due.len() < limit {
+                at += One::one();
+            }
+            visited += 1;
+        }
+
+        if at <= now {
+            SettlementBacklog::<T>::insert(queue, at);
+        } else {
+            SettlementBacklog::<T>::remove(queue);
+        }
+        due
+    }
+
+    /// Upper bound on the storage touched by settling a sale: the payment with its fee and
+    /// royalty, the transfer, and refunding every open offer on the token.
+    fn sale_weight() -> Weight {
+        let offers = Weight::from(T::MaxOffersPerToken::get());
+        T::DbWeight::get().reads_writes(20 + 3 * offers, 20 + 4 * offers)
+    }
+
+    fn expire_dutch_auction(collection_id: T::ClassId, token_id: T::TokenId) {
+        if DutchAuctions::<T>::take(collection_id, token_id).is_some() {
+            Self::set_frozen(collection_id, token_id, false);
+            Self::set_sale_pending(collection_id, false);
+            Self::deposit_event(RawEvent::DutchAuctionExpired(collection_id, token_id));
+        }
+    }
+
+    fn insert_listing(
+        who: &T::AccountId,
+        collection_id: T::ClassId,
+        token_id: T::TokenId,
+        price: BalanceOf<T>,
+        currency_id: CurrencyIdOf<T>,
+    ) -> DispatchResult {
+        let token = nft::Pallet::<T>::tokens(collection_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
+
+        Self::ensure_owner_or_approved(who, collection_id, token_id, &token.owner)?;
+        Self::ensure_transferable(collection_id)?;
+        ensure!(Self::accepts_currency(collection_id, token_id, currency_id), Error::<T>::CurrencyNotAccepted);
+
+        let mut info = Self::extended_info(collection_id, token_id);
+        ensure!(info.frozen == false, Error::<T>::TokenFrozen);
+
+        info.display_for_sale = true;
+        TokenExtendedInfo::<T>::insert(collection_id, token_id, info);
+        Listings::<T>::insert(collection_id, token_id, Listing { price, currency_id });
+        EscrowListings::<T>::remove(collection_id, token_id);
+        Ok(())
+    }
+
+    fn remove_listing(collection_id: T::ClassId, token_id: T::TokenId) {
+        ListingBuyers::<T>::remove(collection_id, token_id);
+        EscrowListings::<T>::remove(collection_id, token_id);
+        if Listings::<T>::take(collection_id, token_id).is_some() {
+            TokenExtendedInfo::<T>::mutate(collection_id, token_id, |info| {
+                if let Some(info) = info {
+                    info.display_for_sale = false;
+                }
+            });
+            Self::deposit_event(RawEvent::ListingCanceled(collection_id, token_id));
+        }
+    }
+
+    /// A plan the buyer has not started was offered by the previous owner, so it goes with them.
+    fn remove_unstarted_plan(collection_id: T::ClassId, token_id: T::TokenId) {
+        if InstallmentPlans::<T>::get(collection_id, token_id).map_or(false, |plan| plan.next_due.is_none()) {
+            InstallmentPlans::<T>::remove(collection_id, token_id);
+            Self::deposit_event(RawEvent::InstallmentPlanCanceled(collection_id, token_id));
+        }
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
 pub struct ChibaSwapAction<T: Config> {
     collection_id: T::ClassId,
@@ -401,6 +3047,7 @@ impl<T: Config> pallet_atomic_swap::SwapAction<<T as frame_system::Config>::Acco
     ) -> frame_support::dispatch::DispatchResult {
         if let Some(token) = nft::Pallet::<T>::tokens(self.collection_id, self.token_id) {
             ensure!(token.owner == *source, Error::<T>::NotTokenOwner);
+            Module::<T>::ensure_transferable(self.collection_id)?;
 
             let mut info = TokenExtendedInfo::<T>::get(self.collection_id, self.token_id)
                 .unwrap_or_else(|| ExtendedInfo {
@@ -415,6 +3062,8 @@ impl<T: Config> pallet_atomic_swap::SwapAction<<T as frame_system::Config>::Acco
 
             info.frozen = true;
             TokenExtendedInfo::<T>::insert(self.collection_id, self.token_id, info);
+            // keeps the collection transferable until the swap is claimed or canceled
+            Module::<T>::set_sale_pending(self.collection_id, true);
             Ok(())
         } else {
             fail!(Error::<T>::TokenNotFound)
@@ -428,7 +3077,10 @@ impl<T: Config> pallet_atomic_swap::SwapAction<<T as frame_system::Config>::Acco
     ) -> bool {
         if let Some(token) = nft::Pallet::<T>::tokens(self.collection_id, self.token_id) {
             if token.owner == *source {
-                nft::Pallet::<T>::transfer(source, target, (self.collection_id, self.token_id))
+                // the swap is over whether or not the transfer goes through
+                Module::<T>::set_frozen(self.collection_id, self.token_id, false);
+                Module::<T>::set_sale_pending(self.collection_id, false);
+                Module::<T>::transfer_token(source, target, self.collection_id, self.token_id)
                     .is_ok()
             } else {
                 false
@@ -456,6 +3108,7 @@ impl<T: Config> pallet_atomic_swap::SwapAction<<T as frame_system::Config>::Acco
 
                 info.frozen = false;
                 TokenExtendedInfo::<T>::insert(self.collection_id, self.token_id, info);
+                Module::<T>::set_sale_pending(self.collection_id, false);
             }
         }
     }
@@ -1,10 +1,16 @@
 use crate::{self as chiba, ChibaSwapAction};
-use frame_support::{dispatch::DispatchResult, parameter_types};
+use frame_support::{
+    dispatch::DispatchResult,
+    parameter_types,
+    traits::{Currency, OnInitialize, OnUnbalanced},
+};
 use frame_system as system;
+use orml_traits::parameter_type_with_key;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
+    ModuleId, Perbill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -19,6 +25,7 @@ frame_support::construct_runtime!(
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         Nft: orml_nft::{Pallet, Call, Storage},
+        Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
         Chiba: chiba::{Pallet, Call, Storage, Event<T>},
         AtomicSwap: pallet_atomic_swap::{Pallet, Call, Storage, Event<T>},
     }
@@ -68,6 +75,22 @@ impl pallet_balances::Config for Test {
     type WeightInfo = ();
 }
 
+parameter_type_with_key! {
+    pub ExistentialDeposits: |_currency_id: u32| -> u64 {
+        0
+    };
+}
+
+impl orml_tokens::Config for Test {
+    type Event = Event;
+    type Balance = u64;
+    type Amount = i64;
+    type CurrencyId = u32;
+    type WeightInfo = ();
+    type ExistentialDeposits = ExistentialDeposits;
+    type OnDust = ();
+}
+
 impl orml_nft::Config for Test {
     type ClassId = u64;
     type TokenId = u64;
@@ -77,11 +100,64 @@ impl orml_nft::Config for Test {
 
 parameter_types! {
     pub const ProofLimit: u32 = 10_000;
+    pub const AuctionExtendPeriod: u64 = 5;
+    pub const MaxSealedBids: u32 = 10;
+    pub const UnrevealedBidSlash: Perbill = Perbill::from_percent(10);
+    pub const MaxRoyalty: Perbill = Perbill::from_percent(50);
+    pub const MarketplaceFee: Perbill = Perbill::from_percent(0);
+    pub const MaxMarketplaceFee: Perbill = Perbill::from_percent(10);
+    pub const MaxOfferExpiriesPerBlock: u32 = 2;
+    pub const MaxSettlementsPerBlock: u32 = 3;
+    pub const OfferReapReward: u64 = 5;
+    pub const MaxNegotiationRounds: u32 = 4;
+    pub const NativeCurrencyId: u32 = NATIVE;
+    pub const FeeCollector: u64 = TREASURY;
+    pub const MaxAcceptedCurrencies: u32 = 2;
+    pub const MaxBundleSize: u32 = 3;
+    pub const MaxOffersPerToken: u32 = 2;
+    pub const MaxOffersPerAccount: u32 = 3;
+    pub const MaxPrivateBuyers: u32 = 2;
+    pub const EscrowModuleId: ModuleId = ModuleId(*b"chb/escr");
+    pub const EscrowPeriod: u64 = 10;
+    pub const MaxInstallments: u32 = 4;
+    pub const MaxAllowlistBatch: u32 = 3;
+    pub const MaxProofLength: u32 = 8;
+}
+
+pub struct FeeToTreasury;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Test>> for FeeToTreasury {
+    fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Test>) {
+        Balances::resolve_creating(&TREASURY, amount);
+    }
 }
 
 impl chiba::Config for Test {
     type Event = Event;
     type Currency = Balances;
+    type AuctionExtendPeriod = AuctionExtendPeriod;
+    type MaxSealedBids = MaxSealedBids;
+    type UnrevealedBidSlash = UnrevealedBidSlash;
+    type MaxRoyalty = MaxRoyalty;
+    type MarketplaceFee = MarketplaceFee;
+    type MaxMarketplaceFee = MaxMarketplaceFee;
+    type FeeDestination = FeeToTreasury;
+    type MaxOfferExpiriesPerBlock = MaxOfferExpiriesPerBlock;
+    type MaxSettlementsPerBlock = MaxSettlementsPerBlock;
+    type OfferReapReward = OfferReapReward;
+    type MaxNegotiationRounds = MaxNegotiationRounds;
+    type MultiCurrency = Tokens;
+    type NativeCurrencyId = NativeCurrencyId;
+    type FeeCollector = FeeCollector;
+    type MaxAcceptedCurrencies = MaxAcceptedCurrencies;
+    type MaxBundleSize = MaxBundleSize;
+    type MaxOffersPerToken = MaxOffersPerToken;
+    type MaxOffersPerAccount = MaxOffersPerAccount;
+    type MaxPrivateBuyers = MaxPrivateBuyers;
+    type EscrowModuleId = EscrowModuleId;
+    type EscrowPeriod = EscrowPeriod;
+    type MaxInstallments = MaxInstallments;
+    type MaxAllowlistBatch = MaxAllowlistBatch;
+    type MaxProofLength = MaxProofLength;
 }
 
 impl pallet_atomic_swap::Config for Test {
@@ -93,6 +169,12 @@ impl pallet_atomic_swap::Config for Test {
 pub const ALICE: u64 = 221;
 pub const BOB: u64 = 1983;
 pub const CURATOR: u64 = 128;
+pub const CHARLIE: u64 = 314;
+pub const TREASURY: u64 = 999;
+
+pub const NATIVE: u32 = 0;
+pub const USD: u32 = 1;
+pub const EUR: u32 = 2;
 
 pub fn new_test_ext() -> sp_io::TestExternalities {
     let mut storage = system::GenesisConfig::default()
@@ -100,7 +182,13 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .unwrap();
 
     pallet_balances::GenesisConfig::<Test> {
-        balances: vec![(ALICE, 1 << 60), (BOB, 1 << 60)],
+        balances: vec![(ALICE, 1 << 60), (BOB, 1 << 60), (CHARLIE, 1 << 60)],
+    }
+    .assimilate_storage(&mut storage)
+    .unwrap();
+
+    orml_tokens::GenesisConfig::<Test> {
+        endowed_accounts: vec![(BOB, USD, 1_000_000), (CHARLIE, EUR, 1_000_000)],
     }
     .assimilate_storage(&mut storage)
     .unwrap();
@@ -113,6 +201,13 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     ext
 }
 
+pub fn run_to_block(n: u64) {
+    while System::block_number() < n {
+        System::set_block_number(System::block_number() + 1);
+        Chiba::on_initialize(System::block_number());
+    }
+}
+
 pub fn last_event() -> crate::mock::Event {
     frame_system::Pallet::<crate::mock::Test>::events()
         .pop()
@@ -125,6 +220,7 @@ pub fn create_default_collection() -> DispatchResult {
         Origin::signed(ALICE),
         Default::default(),
         Default::default(),
+        None,
     )
 }
 
@@ -0,0 +1,23 @@
+//! Runtime API exposing read-only marketplace queries to RPC and front-ends.
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait ChibaApi<AccountId, ClassId, TokenId, CurrencyId, Balance> where
+        AccountId: Codec,
+        ClassId: Codec,
+        TokenId: Codec,
+        CurrencyId: Codec,
+        Balance: Codec,
+    {
+        /// Price a buyer would pay right now for a token in a Dutch auction.
+        fn dutch_auction_price(collection_id: ClassId, token_id: TokenId) -> Option<Balance>;
+
+        /// Open offers made by an account, as `(collection, token, currency, amount)`.
+        fn account_offers(account: AccountId) -> Vec<(ClassId, TokenId, CurrencyId, Balance)>;
+
+        /// Maximum number of tokens a collection may mint, if capped.
+        fn max_supply(collection_id: ClassId) -> Option<TokenId>;
+    }
+}
@@ -1,5 +1,15 @@
 use crate::mock::*;
 use frame_support::{assert_noop, assert_ok, dispatch::DispatchError};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash},
+    Perbill,
+};
+
+fn commitment(bidder: u64, amount: u64, salt: H256) -> H256 {
+    BlakeTwo256::hash_of(&(bidder, amount, salt))
+}
 
 #[test]
 fn set_curator() {
@@ -257,11 +267,13 @@ fn create_offer() {
             Default::default(),
             Default::default(),
             Default::default(),
+            NATIVE,
+            None,
         ));
 
         assert_eq!(
             last_event(),
-            crate::mock::Event::chiba(crate::RawEvent::OfferCreated(0, 0, 0, BOB)),
+            crate::mock::Event::chiba(crate::RawEvent::OfferCreated(0, 0, 0, NATIVE, BOB)),
         );
     });
 }
@@ -277,6 +289,8 @@ fn accept_offer() {
             Default::default(),
             Default::default(),
             Default::default(),
+            NATIVE,
+            None,
         ));
 
         assert_ok!(Chiba::accept_offer(
@@ -304,6 +318,8 @@ fn accept_offer_not_owner() {
             Default::default(),
             Default::default(),
             Default::default(),
+            NATIVE,
+            None,
         ));
 
         assert_noop!(
@@ -329,6 +345,8 @@ fn cancel_offer() {
             Default::default(),
             Default::default(),
             Default::default(),
+            NATIVE,
+            None,
         ));
 
         assert_ok!(Chiba::cancel_offer(
@@ -440,3 +458,2575 @@ fn burn() {
         );
     });
 }
+
+#[test]
+fn list_token() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::list_token(
+            Origin::signed(ALICE),
+            Default::default(),
+            Default::default(),
+            100,
+            NATIVE,
+        ));
+
+        assert_eq!(Chiba::listing(0, 0), Some(crate::Listing { price: 100, currency_id: NATIVE }));
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().display_for_sale, true);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenListed(0, 0, 100, NATIVE)),
+        );
+    });
+}
+
+#[test]
+fn toggle_for_sale_requires_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::toggle_marketplace_listing(Origin::signed(ALICE), 0, 0, true, true),
+            crate::Error::<Test>::ListingNotFound
+        );
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 100, NATIVE));
+        assert_ok!(Chiba::toggle_marketplace_listing(Origin::signed(ALICE), 0, 0, true, true));
+
+        assert_ok!(Chiba::toggle_marketplace_listing(Origin::signed(ALICE), 0, 0, true, false));
+        assert_eq!(Chiba::listing(0, 0), None);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().display_for_sale, false);
+    });
+}
+
+#[test]
+fn list_token_not_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::list_token(
+                Origin::signed(BOB),
+                Default::default(),
+                Default::default(),
+                100,
+                NATIVE,
+            ),
+            crate::Error::<Test>::NotTokenOwner,
+        );
+    });
+}
+
+#[test]
+fn cancel_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 100, NATIVE));
+
+        assert_ok!(Chiba::cancel_listing(Origin::signed(ALICE), 0, 0));
+
+        assert_eq!(Chiba::listing(0, 0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::ListingCanceled(0, 0)),
+        );
+    });
+}
+
+#[test]
+fn buy_now() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 100, NATIVE));
+
+        assert_ok!(Chiba::buy_now(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 100);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 100);
+        assert_eq!(Chiba::listing(0, 0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenSold(0, 0, ALICE, BOB, 100, NATIVE)),
+        );
+    });
+}
+
+#[test]
+fn buy_now_not_listed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::buy_now(Origin::signed(BOB), 0, 0),
+            crate::Error::<Test>::ListingNotFound,
+        );
+    });
+}
+
+#[test]
+fn buy_now_own_token() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 100, NATIVE));
+
+        assert_noop!(
+            Chiba::buy_now(Origin::signed(ALICE), 0, 0),
+            crate::Error::<Test>::CannotBuyOwnToken,
+        );
+    });
+}
+
+#[test]
+fn transfer_removes_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 100, NATIVE));
+
+        assert_ok!(Chiba::transfer(Origin::signed(ALICE), 0, 0, BOB));
+
+        assert_eq!(Chiba::listing(0, 0), None);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().display_for_sale, false);
+    });
+}
+
+#[test]
+fn burn_removes_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 100, NATIVE));
+
+        assert_ok!(Chiba::burn(Origin::signed(ALICE), 0, 0));
+
+        assert_eq!(Chiba::listing(0, 0), None);
+    });
+}
+
+#[test]
+fn create_auction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, true);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::AuctionCreated(0, 0, 100, 20)),
+        );
+        assert_noop!(
+            Chiba::transfer(Origin::signed(ALICE), 0, 0, BOB),
+            crate::Error::<Test>::TokenFrozen,
+        );
+    });
+}
+
+#[test]
+fn create_auction_invalid_end() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 1),
+            crate::Error::<Test>::InvalidAuctionEnd,
+        );
+    });
+}
+
+#[test]
+fn bid() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+
+        assert_eq!(Balances::reserved_balance(BOB), 100);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::BidPlaced(0, 0, BOB, 100)),
+        );
+    });
+}
+
+#[test]
+fn bid_below_reserve_or_increment() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+
+        assert_noop!(
+            Chiba::bid(Origin::signed(BOB), 0, 0, 99),
+            crate::Error::<Test>::BidTooLow,
+        );
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+        assert_noop!(
+            Chiba::bid(Origin::signed(CHARLIE), 0, 0, 109),
+            crate::Error::<Test>::BidTooLow,
+        );
+    });
+}
+
+#[test]
+fn bid_refunds_previous_bidder() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+        assert_ok!(Chiba::bid(Origin::signed(CHARLIE), 0, 0, 110));
+
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::reserved_balance(CHARLIE), 110);
+    });
+}
+
+#[test]
+fn bid_extends_auction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+
+        run_to_block(18);
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+
+        assert_eq!(Chiba::auction(0, 0).unwrap().end, 23);
+        assert_eq!(Chiba::auction_endings(20, (0, 0)), None);
+        assert_eq!(Chiba::auction_endings(23, (0, 0)), Some(()));
+    });
+}
+
+#[test]
+fn auction_settles() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+
+        run_to_block(20);
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Chiba::auction(0, 0), None);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 100);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::AuctionSettled(0, 0, BOB, 100)),
+        );
+    });
+}
+
+#[test]
+fn auction_ends_without_bids() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+
+        run_to_block(20);
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, ALICE);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::AuctionEndedWithoutSale(0, 0)),
+        );
+    });
+}
+
+#[test]
+fn auction_settlements_carry_over() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(mint_default_token());
+        for token_id in 0..4 {
+            if token_id > 0 {
+                assert_ok!(Chiba::mint(Origin::signed(ALICE), 0, vec![], Default::default()));
+            }
+            assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, token_id, 100, 10, 20));
+        }
+
+        run_to_block(20);
+
+        // three settle at the end block, the last one is left for the next
+        let open = || (0..4).filter(|&token_id| Chiba::auction(0, token_id).is_some()).count();
+        assert_eq!(open(), 1);
+        assert_eq!(Chiba::settlement_backlog(crate::SettlementQueue::Auctions), Some(20));
+
+        run_to_block(21);
+
+        assert_eq!(open(), 0);
+        assert_eq!(Chiba::settlement_backlog(crate::SettlementQueue::Auctions), None);
+    });
+}
+
+#[test]
+fn settle_failed_auction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+        assert_noop!(
+            Chiba::settle(Origin::signed(CHARLIE), 0, 0),
+            crate::Error::<Test>::NothingToSettle
+        );
+
+        // make the transfer fail when the auction is settled
+        crate::CollectionFlags::<Test>::insert(0, crate::CollectionSettings(0));
+        run_to_block(20);
+
+        assert!(Chiba::auction(0, 0).is_some());
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, true);
+        assert_eq!(Balances::reserved_balance(BOB), 100);
+
+        crate::CollectionFlags::<Test>::remove(0);
+        assert_ok!(Chiba::settle(Origin::signed(CHARLIE), 0, 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Chiba::auction(0, 0), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
+
+#[test]
+fn cancel_auction_with_bids() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+
+        assert_noop!(
+            Chiba::cancel_auction(Origin::signed(ALICE), 0, 0),
+            crate::Error::<Test>::AuctionHasBids,
+        );
+    });
+}
+
+#[test]
+fn create_dutch_auction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::create_dutch_auction(Origin::signed(ALICE), 0, 0, 1000, 100, 10, 30));
+
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, true);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::DutchAuctionStarted(0, 0, 1000, 100, 30)),
+        );
+    });
+}
+
+#[test]
+fn create_dutch_auction_invalid_prices() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::create_dutch_auction(Origin::signed(ALICE), 0, 0, 100, 1000, 10, 30),
+            crate::Error::<Test>::InvalidPriceRange,
+        );
+    });
+}
+
+#[test]
+fn dutch_auction_price_decays() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_dutch_auction(Origin::signed(ALICE), 0, 0, 1000, 100, 10, 30));
+
+        assert_eq!(Chiba::dutch_auction_price(0, 0), Some(1000));
+        run_to_block(6);
+        assert_eq!(Chiba::dutch_auction_price(0, 0), Some(550));
+        run_to_block(20);
+        assert_eq!(Chiba::dutch_auction_price(0, 0), Some(100));
+    });
+}
+
+#[test]
+fn buy_dutch_auction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_dutch_auction(Origin::signed(ALICE), 0, 0, 1000, 100, 10, 30));
+
+        run_to_block(6);
+        assert_ok!(Chiba::buy(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 550);
+        assert_eq!(Chiba::dutch_auction(0, 0), None);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::DutchAuctionPurchased(0, 0, BOB, 550)),
+        );
+    });
+}
+
+#[test]
+fn dutch_auction_expires() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_dutch_auction(Origin::signed(ALICE), 0, 0, 1000, 100, 10, 30));
+
+        run_to_block(30);
+
+        assert_eq!(Chiba::dutch_auction(0, 0), None);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::DutchAuctionExpired(0, 0)),
+        );
+        assert_noop!(
+            Chiba::buy(Origin::signed(BOB), 0, 0),
+            crate::Error::<Test>::AuctionNotFound,
+        );
+    });
+}
+
+#[test]
+fn commit_bid() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_sealed_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+
+        assert_ok!(Chiba::commit_bid(
+            Origin::signed(BOB),
+            0,
+            0,
+            commitment(BOB, 150, H256::repeat_byte(1)),
+            200,
+        ));
+
+        assert_eq!(Balances::reserved_balance(BOB), 200);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::BidCommitted(0, 0, BOB)),
+        );
+        assert_noop!(
+            Chiba::commit_bid(Origin::signed(BOB), 0, 0, commitment(BOB, 150, H256::repeat_byte(1)), 200),
+            crate::Error::<Test>::BidAlreadyCommitted,
+        );
+    });
+}
+
+#[test]
+fn reveal_bid_outside_window() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_sealed_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::commit_bid(Origin::signed(BOB), 0, 0, commitment(BOB, 150, H256::repeat_byte(1)), 200));
+
+        assert_noop!(
+            Chiba::reveal_bid(Origin::signed(BOB), 0, 0, 150, H256::repeat_byte(1)),
+            crate::Error::<Test>::NotInRevealPhase,
+        );
+        run_to_block(10);
+        assert_noop!(
+            Chiba::commit_bid(Origin::signed(CHARLIE), 0, 0, commitment(CHARLIE, 150, H256::repeat_byte(2)), 200),
+            crate::Error::<Test>::NotInCommitPhase,
+        );
+    });
+}
+
+#[test]
+fn reveal_bid_wrong_salt() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_sealed_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::commit_bid(Origin::signed(BOB), 0, 0, commitment(BOB, 150, H256::repeat_byte(1)), 200));
+
+        run_to_block(10);
+        assert_noop!(
+            Chiba::reveal_bid(Origin::signed(BOB), 0, 0, 150, H256::repeat_byte(2)),
+            crate::Error::<Test>::InvalidReveal,
+        );
+    });
+}
+
+#[test]
+fn reveal_copied_commitment() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_sealed_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        let copied = commitment(BOB, 150, H256::repeat_byte(1));
+        assert_ok!(Chiba::commit_bid(Origin::signed(BOB), 0, 0, copied, 200));
+        assert_ok!(Chiba::commit_bid(Origin::signed(CHARLIE), 0, 0, copied, 200));
+
+        run_to_block(10);
+        assert_noop!(
+            Chiba::reveal_bid(Origin::signed(CHARLIE), 0, 0, 150, H256::repeat_byte(1)),
+            crate::Error::<Test>::InvalidReveal,
+        );
+        assert_ok!(Chiba::reveal_bid(Origin::signed(BOB), 0, 0, 150, H256::repeat_byte(1)));
+    });
+}
+
+#[test]
+fn sealed_auction_settles() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_sealed_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::commit_bid(Origin::signed(BOB), 0, 0, commitment(BOB, 150, H256::repeat_byte(1)), 200));
+        assert_ok!(Chiba::commit_bid(Origin::signed(CHARLIE), 0, 0, commitment(CHARLIE, 120, H256::repeat_byte(2)), 120));
+
+        run_to_block(10);
+        assert_ok!(Chiba::reveal_bid(Origin::signed(BOB), 0, 0, 150, H256::repeat_byte(1)));
+        assert_ok!(Chiba::reveal_bid(Origin::signed(CHARLIE), 0, 0, 120, H256::repeat_byte(2)));
+        assert_eq!(Chiba::sealed_auction(0, 0).unwrap().best, Some((BOB, 150)));
+
+        run_to_block(20);
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 150);
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), 1 << 60);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 150);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::AuctionSettled(0, 0, BOB, 150)),
+        );
+    });
+}
+
+#[test]
+fn sealed_auction_slashes_unrevealed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_sealed_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::commit_bid(Origin::signed(BOB), 0, 0, commitment(BOB, 150, H256::repeat_byte(1)), 200));
+
+        run_to_block(20);
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, ALICE);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 20);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::AuctionEndedWithoutSale(0, 0)),
+        );
+    });
+}
+
+#[test]
+fn set_collection_royalty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+
+        assert_ok!(Chiba::set_collection_royalty(Origin::signed(ALICE), 0, CHARLIE, Perbill::from_percent(10)));
+
+        assert_eq!(
+            Chiba::collection_royalty(0),
+            Some(crate::Royalty { beneficiary: CHARLIE, rate: Perbill::from_percent(10) }),
+        );
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::CollectionRoyaltySet(0, CHARLIE, Perbill::from_percent(10))),
+        );
+    });
+}
+
+#[test]
+fn set_collection_royalty_too_high() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+
+        assert_noop!(
+            Chiba::set_collection_royalty(Origin::signed(ALICE), 0, CHARLIE, Perbill::from_percent(51)),
+            crate::Error::<Test>::RoyaltyTooHigh,
+        );
+        assert_noop!(
+            Chiba::set_collection_royalty(Origin::signed(BOB), 0, CHARLIE, Perbill::from_percent(10)),
+            crate::Error::<Test>::NotCollectionOwner,
+        );
+    });
+}
+
+#[test]
+fn accept_offer_pays_royalty() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_collection_royalty(Origin::signed(ALICE), 0, CHARLIE, Perbill::from_percent(10)));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 1000, NATIVE, None));
+
+        assert_ok!(Chiba::accept_offer(Origin::signed(ALICE), 0, 0, BOB));
+
+        assert_eq!(Balances::free_balance(CHARLIE), (1 << 60) + 100);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 900);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert!(System::events().iter().any(|record| record.event
+            == crate::mock::Event::chiba(crate::RawEvent::RoyaltyPaid(0, 0, CHARLIE, 100))));
+    });
+}
+
+#[test]
+fn token_royalty_overrides_collection() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_collection_royalty(Origin::signed(ALICE), 0, CHARLIE, Perbill::from_percent(10)));
+        assert_ok!(Chiba::set_token_royalty(Origin::signed(ALICE), 0, 0, CURATOR, Perbill::from_percent(20)));
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, NATIVE));
+
+        assert_ok!(Chiba::buy_now(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Balances::free_balance(CURATOR), 200);
+        assert_eq!(Balances::free_balance(CHARLIE), 1 << 60);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 800);
+    });
+}
+
+#[test]
+fn set_marketplace_fee() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(Chiba::marketplace_fee(), Perbill::from_percent(0));
+
+        assert_ok!(Chiba::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+
+        assert_eq!(Chiba::marketplace_fee(), Perbill::from_percent(5));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::MarketplaceFeeSet(Perbill::from_percent(5))),
+        );
+    });
+}
+
+#[test]
+fn set_marketplace_fee_bounds() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            Chiba::set_marketplace_fee(Origin::root(), Perbill::from_percent(11)),
+            crate::Error::<Test>::FeeTooHigh,
+        );
+        assert_noop!(
+            Chiba::set_marketplace_fee(Origin::signed(ALICE), Perbill::from_percent(5)),
+            DispatchError::BadOrigin,
+        );
+    });
+}
+
+#[test]
+fn accept_offer_pays_marketplace_fee() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+        assert_ok!(Chiba::set_collection_royalty(Origin::signed(ALICE), 0, CHARLIE, Perbill::from_percent(10)));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 1000, NATIVE, None));
+
+        assert_ok!(Chiba::accept_offer(Origin::signed(ALICE), 0, 0, BOB));
+
+        assert_eq!(Balances::free_balance(TREASURY), 50);
+        assert_eq!(Balances::free_balance(CHARLIE), (1 << 60) + 100);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 850);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
+
+#[test]
+fn buy_now_pays_marketplace_fee() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, NATIVE));
+
+        assert_ok!(Chiba::buy_now(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Balances::free_balance(TREASURY), 50);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 950);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 1000);
+    });
+}
+
+#[test]
+fn create_offer_invalid_expiry() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, Some(1)),
+            crate::Error::<Test>::InvalidExpiry,
+        );
+    });
+}
+
+#[test]
+fn offer_expires_in_on_initialize() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, Some(10)));
+        assert_eq!(Balances::reserved_balance(BOB), 100);
+
+        run_to_block(10);
+
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::OfferExpired(0, 0, BOB)),
+        );
+    });
+}
+
+#[test]
+fn accept_expired_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, Some(10)));
+
+        // skip the hook so the offer is still stored once it has expired
+        System::set_block_number(10);
+
+        assert_noop!(
+            Chiba::accept_offer(Origin::signed(ALICE), 0, 0, BOB),
+            crate::Error::<Test>::ExpiredOffer,
+        );
+    });
+}
+
+#[test]
+fn reap_expired_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, Some(10)));
+
+        assert_noop!(
+            Chiba::reap_expired_offer(Origin::signed(CHARLIE), 0, 0, BOB),
+            crate::Error::<Test>::OfferNotExpired,
+        );
+
+        System::set_block_number(10);
+        assert_ok!(Chiba::reap_expired_offer(Origin::signed(CHARLIE), 0, 0, BOB));
+
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+        assert_eq!(Chiba::offer_expiries(10, ((0, 0), BOB)), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 5);
+        assert_eq!(Balances::free_balance(CHARLIE), (1 << 60) + 5);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::OfferExpired(0, 0, BOB)),
+        );
+    });
+}
+
+#[test]
+fn offer_expiries_are_bounded_per_block() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, Some(10)));
+        assert_ok!(Chiba::create_offer(Origin::signed(CHARLIE), 0, 0, 100, NATIVE, Some(10)));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 1, 100, NATIVE, Some(10)));
+
+        run_to_block(10);
+
+        assert_eq!(Balances::reserved_balance(BOB) + Balances::reserved_balance(CHARLIE), 100);
+    });
+}
+
+#[test]
+fn create_collection_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+
+        assert_ok!(Chiba::create_collection_offer(Origin::signed(BOB), 0, 100, NATIVE, 3));
+
+        assert_eq!(Balances::reserved_balance(BOB), 300);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::CollectionOfferCreated(0, BOB, 100, 3)),
+        );
+        assert_noop!(
+            Chiba::create_collection_offer(Origin::signed(BOB), 0, 100, NATIVE, 3),
+            crate::Error::<Test>::OfferAlreadyExists,
+        );
+        assert_noop!(
+            Chiba::create_collection_offer(Origin::signed(CHARLIE), 0, 100, NATIVE, 0),
+            crate::Error::<Test>::InvalidQuantity,
+        );
+    });
+}
+
+#[test]
+fn accept_collection_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_collection_offer(Origin::signed(BOB), 0, 100, NATIVE, 2));
+
+        assert_ok!(Chiba::accept_collection_offer(Origin::signed(ALICE), 0, 1, BOB));
+
+        assert_eq!(Nft::tokens(0, 1).unwrap().owner, BOB);
+        assert_eq!(Chiba::collection_offer(0, BOB).unwrap().quantity, 1);
+        assert_eq!(Balances::reserved_balance(BOB), 100);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 100);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::CollectionOfferFilled(0, 1, ALICE, BOB, 100)),
+        );
+
+        assert_ok!(Chiba::accept_collection_offer(Origin::signed(ALICE), 0, 0, BOB));
+
+        assert_eq!(Chiba::collection_offer(0, BOB), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
+
+#[test]
+fn accept_collection_offer_not_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_collection_offer(Origin::signed(BOB), 0, 100, NATIVE, 1));
+
+        assert_noop!(
+            Chiba::accept_collection_offer(Origin::signed(CHARLIE), 0, 0, BOB),
+            crate::Error::<Test>::NotTokenOwner,
+        );
+    });
+}
+
+#[test]
+fn cancel_collection_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(Chiba::create_collection_offer(Origin::signed(BOB), 0, 100, NATIVE, 3));
+
+        assert_ok!(Chiba::cancel_collection_offer(Origin::signed(BOB), 0));
+
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::CollectionOfferCanceled(0, BOB)),
+        );
+    });
+}
+
+#[test]
+fn counter_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+
+        assert_ok!(Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 150));
+
+        let offer = Chiba::offer((0, 0), BOB).unwrap();
+        assert_eq!(offer.counter, Some(150));
+        assert_eq!(offer.turn, crate::Party::Buyer);
+        assert_eq!(offer.rounds, vec![(crate::Party::Buyer, 100), (crate::Party::Owner, 150)]);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::OfferCountered(0, 0, BOB, 150)),
+        );
+        assert_noop!(
+            Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 140),
+            crate::Error::<Test>::NotYourTurn,
+        );
+    });
+}
+
+#[test]
+fn counter_offer_not_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+
+        assert_noop!(
+            Chiba::counter_offer(Origin::signed(CHARLIE), 0, 0, BOB, 150),
+            crate::Error::<Test>::NotTokenOwner,
+        );
+    });
+}
+
+#[test]
+fn raise_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+
+        assert_noop!(
+            Chiba::raise_offer(Origin::signed(BOB), 0, 0, 120),
+            crate::Error::<Test>::NotYourTurn,
+        );
+        assert_ok!(Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 150));
+        assert_ok!(Chiba::raise_offer(Origin::signed(BOB), 0, 0, 120));
+
+        assert_eq!(Balances::reserved_balance(BOB), 120);
+        assert_eq!(Chiba::offer((0, 0), BOB).unwrap().turn, crate::Party::Owner);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::OfferRaised(0, 0, BOB, 120)),
+        );
+    });
+}
+
+#[test]
+fn negotiation_round_limit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 150));
+        assert_ok!(Chiba::raise_offer(Origin::signed(BOB), 0, 0, 120));
+        assert_ok!(Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 140));
+
+        assert_noop!(
+            Chiba::raise_offer(Origin::signed(BOB), 0, 0, 130),
+            crate::Error::<Test>::TooManyRounds,
+        );
+    });
+}
+
+#[test]
+fn accept_counter_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 150));
+
+        assert_ok!(Chiba::accept_counter_offer(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 150);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 150);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::CounterOfferAccepted(0, 0, ALICE, BOB, 150)),
+        );
+    });
+}
+
+#[test]
+fn accept_offer_in_other_currency() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 1000, USD, None));
+
+        assert_eq!(Tokens::reserved_balance(USD, &BOB), 1000);
+
+        assert_ok!(Chiba::accept_offer(Origin::signed(ALICE), 0, 0, BOB));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Tokens::reserved_balance(USD, &BOB), 0);
+        assert_eq!(Tokens::free_balance(USD, &BOB), 1_000_000 - 1000);
+        assert_eq!(Tokens::free_balance(USD, &ALICE), 950);
+        assert_eq!(Tokens::free_balance(USD, &TREASURY), 50);
+        assert_eq!(Balances::free_balance(ALICE), 1 << 60);
+    });
+}
+
+#[test]
+fn buy_now_in_other_currency() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, USD));
+
+        assert_ok!(Chiba::buy_now(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Tokens::free_balance(USD, &ALICE), 1000);
+        assert_eq!(Balances::free_balance(BOB), 1 << 60);
+    });
+}
+
+#[test]
+fn set_accepted_currencies() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::set_accepted_currencies(Origin::signed(ALICE), 0, 0, vec![NATIVE, USD]));
+
+        assert_eq!(Chiba::accepted_currencies(0, 0), Some(vec![NATIVE, USD]));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::AcceptedCurrenciesSet(0, 0, vec![NATIVE, USD])),
+        );
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(CHARLIE), 0, 0, 1000, EUR, None),
+            crate::Error::<Test>::CurrencyNotAccepted,
+        );
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 1000, USD, None));
+    });
+}
+
+#[test]
+fn set_accepted_currencies_too_many() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::set_accepted_currencies(Origin::signed(ALICE), 0, 0, vec![NATIVE, USD, EUR]),
+            crate::Error::<Test>::TooManyCurrencies,
+        );
+    });
+}
+
+#[test]
+fn accepted_currencies_cleared_on_transfer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_accepted_currencies(Origin::signed(ALICE), 0, 0, vec![USD]));
+
+        assert_ok!(Chiba::transfer(Origin::signed(ALICE), 0, 0, BOB));
+
+        assert_eq!(Chiba::accepted_currencies(0, 0), None);
+        assert_ok!(Chiba::list_token(Origin::signed(BOB), 0, 0, 100, NATIVE));
+    });
+}
+
+#[test]
+fn list_bundle() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::list_bundle(Origin::signed(ALICE), vec![(0, 0), (0, 1)], 1000, NATIVE));
+
+        assert_eq!(Chiba::next_bundle_id(), 1);
+        assert_eq!(Chiba::bundle_listing(0).unwrap().items, vec![(0, 0), (0, 1)]);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::BundleListed(0, ALICE, 1000)),
+        );
+    });
+}
+
+#[test]
+fn list_bundle_invalid() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::list_bundle(Origin::signed(ALICE), vec![], 1000, NATIVE),
+            crate::Error::<Test>::InvalidBundle,
+        );
+        assert_noop!(
+            Chiba::list_bundle(Origin::signed(ALICE), vec![(0, 0), (0, 0)], 1000, NATIVE),
+            crate::Error::<Test>::InvalidBundle,
+        );
+        assert_noop!(
+            Chiba::list_bundle(Origin::signed(ALICE), vec![(0, 0), (0, 1), (0, 2), (0, 3)], 1000, NATIVE),
+            crate::Error::<Test>::InvalidBundle,
+        );
+        assert_noop!(
+            Chiba::list_bundle(Origin::signed(BOB), vec![(0, 0), (0, 1)], 1000, NATIVE),
+            crate::Error::<Test>::NotTokenOwner,
+        );
+    });
+}
+
+#[test]
+fn buy_bundle() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_bundle(Origin::signed(ALICE), vec![(0, 0), (0, 1)], 1001, NATIVE));
+
+        assert_ok!(Chiba::buy_bundle(Origin::signed(BOB), 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Nft::tokens(0, 1).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 1001);
+        assert_eq!(Chiba::bundle_listing(0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::BundleSold(0, ALICE, BOB, 1001)),
+        );
+    });
+}
+
+#[test]
+fn buy_bundle_is_all_or_nothing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_bundle(Origin::signed(ALICE), vec![(0, 0), (0, 1)], 1000, NATIVE));
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 1, 100, 10, 20));
+
+        assert_noop!(
+            Chiba::buy_bundle(Origin::signed(BOB), 0),
+            crate::Error::<Test>::TokenFrozen,
+        );
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, ALICE);
+    });
+}
+
+#[test]
+fn bundle_listing_canceled_on_transfer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_bundle(Origin::signed(ALICE), vec![(0, 0), (0, 1)], 1000, NATIVE));
+        assert_noop!(
+            Chiba::list_bundle(Origin::signed(ALICE), vec![(0, 1)], 500, NATIVE),
+            crate::Error::<Test>::AlreadyBundled,
+        );
+
+        assert_ok!(Chiba::transfer(Origin::signed(ALICE), 0, 0, BOB));
+        assert_ok!(Chiba::transfer(Origin::signed(BOB), 0, 0, ALICE));
+
+        assert_eq!(Chiba::bundle_listing(0), None);
+        assert_eq!(Chiba::bundled_in(0, 1), None);
+        assert_noop!(
+            Chiba::buy_bundle(Origin::signed(CHARLIE), 0),
+            crate::Error::<Test>::BundleNotFound,
+        );
+    });
+}
+
+#[test]
+fn accept_bundle_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_bundle_offer(Origin::signed(BOB), vec![(0, 0), (0, 1)], 1000, NATIVE));
+        assert_eq!(Balances::reserved_balance(BOB), 1000);
+
+        assert_ok!(Chiba::accept_bundle_offer(Origin::signed(ALICE), 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Nft::tokens(0, 1).unwrap().owner, BOB);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 1000);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::BundleOfferAccepted(0, ALICE, BOB, 1000)),
+        );
+    });
+}
+
+#[test]
+fn cancel_bundle_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_bundle_offer(Origin::signed(BOB), vec![(0, 0)], 1000, NATIVE));
+
+        assert_ok!(Chiba::cancel_bundle_offer(Origin::signed(BOB), 0));
+
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::BundleOfferCanceled(0)),
+        );
+    });
+}
+
+#[test]
+fn transfer_refunds_offers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, Some(10)));
+
+        assert_ok!(Chiba::transfer(Origin::signed(ALICE), 0, 0, CHARLIE));
+
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+        assert_eq!(Chiba::offer_expiries(10, ((0, 0), BOB)), None);
+        assert_eq!(Chiba::offer_count(0, 0), 0);
+        assert!(System::events().iter().any(|record| record.event
+            == crate::mock::Event::chiba(crate::RawEvent::OfferRefunded(0, 0, BOB))));
+    });
+}
+
+#[test]
+fn accept_offer_refunds_other_offers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::create_offer(Origin::signed(CHARLIE), 0, 0, 200, NATIVE, None));
+
+        assert_ok!(Chiba::accept_offer(Origin::signed(ALICE), 0, 0, CHARLIE));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, CHARLIE);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::reserved_balance(CHARLIE), 0);
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+    });
+}
+
+#[test]
+fn burn_clears_token_state() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+
+        assert_ok!(Chiba::burn(Origin::signed(ALICE), 0, 0));
+
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+        assert!(Chiba::token_extended_info(0, 0).is_none());
+    });
+}
+
+#[test]
+fn create_offer_too_many() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::create_offer(Origin::signed(CHARLIE), 0, 0, 100, NATIVE, None));
+
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(CURATOR), 0, 0, 100, NATIVE, None),
+            crate::Error::<Test>::TooManyOffers
+        );
+    });
+}
+
+#[test]
+fn bidder_offers_are_indexed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 1, 200, USD, None));
+
+        assert_eq!(Chiba::bidder_offers(BOB), vec![(0, 0), (0, 1)]);
+        assert_eq!(Chiba::account_offers(&BOB), vec![(0, 0, NATIVE, 100), (0, 1, USD, 200)]);
+
+        assert_ok!(Chiba::cancel_offer(Origin::signed(BOB), 0, 0));
+        assert_eq!(Chiba::bidder_offers(BOB), vec![(0, 1)]);
+
+        assert_ok!(Chiba::transfer(Origin::signed(ALICE), 0, 1, CHARLIE));
+        assert!(Chiba::bidder_offers(BOB).is_empty());
+    });
+}
+
+#[test]
+fn cancel_all_offers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 1, 200, USD, None));
+
+        assert_ok!(Chiba::cancel_all_offers(Origin::signed(BOB)));
+
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Tokens::reserved_balance(USD, &BOB), 0);
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+        assert_eq!(Chiba::offer((0, 1), BOB), None);
+        assert!(Chiba::bidder_offers(BOB).is_empty());
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::AllOffersCanceled(BOB, 2)),
+        );
+        assert_noop!(
+            Chiba::cancel_all_offers(Origin::signed(BOB)),
+            crate::Error::<Test>::OfferNotFound
+        );
+    });
+}
+
+#[test]
+fn create_offer_too_many_for_account() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        for token_id in 0..4 {
+            assert_ok!(mint_default_token());
+            if token_id < 3 {
+                assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, token_id, 100, NATIVE, None));
+            }
+        }
+
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(BOB), 0, 3, 100, NATIVE, None),
+            crate::Error::<Test>::TooManyAccountOffers
+        );
+    });
+}
+
+#[test]
+fn update_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+
+        assert_ok!(Chiba::update_offer(Origin::signed(BOB), 0, 0, 250));
+        assert_eq!(Balances::reserved_balance(BOB), 250);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::OfferUpdated(0, 0, BOB, 250)),
+        );
+
+        assert_ok!(Chiba::update_offer(Origin::signed(BOB), 0, 0, 50));
+        assert_eq!(Balances::reserved_balance(BOB), 50);
+        assert_eq!(Chiba::offer((0, 0), BOB).unwrap().amount, 50);
+
+        assert_ok!(Chiba::cancel_offer(Origin::signed(BOB), 0, 0));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
+
+#[test]
+fn update_offer_replaces_counter_offer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 150));
+
+        assert_ok!(Chiba::update_offer(Origin::signed(BOB), 0, 0, 200));
+
+        let offer = Chiba::offer((0, 0), BOB).unwrap();
+        assert_eq!(offer.counter, None);
+        assert_eq!(offer.turn, crate::Party::Owner);
+        assert_eq!(offer.rounds.last(), Some(&(crate::Party::Buyer, 200)));
+        assert_noop!(
+            Chiba::accept_counter_offer(Origin::signed(BOB), 0, 0),
+            crate::Error::<Test>::NotYourTurn
+        );
+
+        assert_ok!(Chiba::accept_offer(Origin::signed(ALICE), 0, 0, BOB));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 200);
+    });
+}
+
+#[test]
+fn update_offer_not_found() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::update_offer(Origin::signed(BOB), 0, 0, 100),
+            crate::Error::<Test>::OfferNotFound
+        );
+    });
+}
+
+#[test]
+fn create_offer_rejects_invalid_targets() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(BOB), 0, 0, 200, NATIVE, None),
+            crate::Error::<Test>::OfferAlreadyExists
+        );
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(ALICE), 0, 0, 100, NATIVE, None),
+            crate::Error::<Test>::CannotBuyOwnToken
+        );
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(BOB), 0, 7, 100, NATIVE, None),
+            crate::Error::<Test>::TokenNotFound
+        );
+    });
+}
+
+#[test]
+fn set_sale_settings() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::set_sale_settings(Origin::signed(ALICE), 0, 0, NATIVE, Some(100), Some(500)));
+
+        assert_eq!(
+            Chiba::sale_settings(0, 0),
+            Some(crate::SaleSettings { currency_id: NATIVE, min_offer: Some(100), auto_accept: Some(500) })
+        );
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::SaleSettingsSet(0, 0, NATIVE, Some(100), Some(500))),
+        );
+
+        assert_ok!(Chiba::set_sale_settings(Origin::signed(ALICE), 0, 0, NATIVE, None, None));
+        assert_eq!(Chiba::sale_settings(0, 0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::SaleSettingsCleared(0, 0)),
+        );
+    });
+}
+
+#[test]
+fn set_sale_settings_invalid() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::set_sale_settings(Origin::signed(BOB), 0, 0, NATIVE, Some(100), None),
+            crate::Error::<Test>::NotTokenOwner
+        );
+        assert_noop!(
+            Chiba::set_sale_settings(Origin::signed(ALICE), 0, 0, NATIVE, Some(500), Some(100)),
+            crate::Error::<Test>::InvalidPriceRange
+        );
+    });
+}
+
+#[test]
+fn create_offer_below_minimum() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_sale_settings(Origin::signed(ALICE), 0, 0, NATIVE, Some(100), None));
+
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(BOB), 0, 0, 99, NATIVE, None),
+            crate::Error::<Test>::OfferBelowMinimum
+        );
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(BOB), 0, 0, 1000, USD, None),
+            crate::Error::<Test>::CurrencyNotAccepted
+        );
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_noop!(
+            Chiba::update_offer(Origin::signed(BOB), 0, 0, 50),
+            crate::Error::<Test>::OfferBelowMinimum
+        );
+    });
+}
+
+#[test]
+fn raise_offer_auto_accepted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_sale_settings(Origin::signed(ALICE), 0, 0, NATIVE, None, Some(500)));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        assert_ok!(Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 600));
+
+        assert_ok!(Chiba::raise_offer(Origin::signed(BOB), 0, 0, 500));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Chiba::offer((0, 0), BOB), None);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 500);
+    });
+}
+
+#[test]
+fn create_offer_auto_accepted() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_sale_settings(Origin::signed(ALICE), 0, 0, NATIVE, None, Some(500)));
+
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 400, NATIVE, None));
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, ALICE);
+
+        assert_ok!(Chiba::update_offer(Origin::signed(BOB), 0, 0, 500));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 500);
+        assert_eq!(Chiba::sale_settings(0, 0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::OfferAccepted(0, 0, ALICE, BOB)),
+        );
+    });
+}
+
+#[test]
+fn list_token_privately() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::list_token_privately(Origin::signed(ALICE), 0, 0, 1000, NATIVE, vec![BOB]));
+
+        assert_eq!(Chiba::listing_buyers(0, 0), Some(vec![BOB]));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenListedPrivately(0, 0, 1000, NATIVE, vec![BOB])),
+        );
+    });
+}
+
+#[test]
+fn list_token_privately_invalid_buyers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::list_token_privately(Origin::signed(ALICE), 0, 0, 1000, NATIVE, vec![]),
+            crate::Error::<Test>::InvalidBuyers
+        );
+        assert_noop!(
+            Chiba::list_token_privately(Origin::signed(ALICE), 0, 0, 1000, NATIVE, vec![BOB, CHARLIE, CURATOR]),
+            crate::Error::<Test>::InvalidBuyers
+        );
+    });
+}
+
+#[test]
+fn buy_private_listing() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token_privately(Origin::signed(ALICE), 0, 0, 1000, NATIVE, vec![BOB]));
+
+        assert_noop!(
+            Chiba::buy_now(Origin::signed(CHARLIE), 0, 0),
+            crate::Error::<Test>::NotAllowedBuyer
+        );
+        assert_ok!(Chiba::buy_now(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Chiba::listing_buyers(0, 0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenSold(0, 0, ALICE, BOB, 1000, NATIVE)),
+        );
+    });
+}
+
+#[test]
+fn public_relisting_lifts_restriction() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token_privately(Origin::signed(ALICE), 0, 0, 1000, NATIVE, vec![BOB]));
+
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, NATIVE));
+
+        assert_ok!(Chiba::buy_now(Origin::signed(CHARLIE), 0, 0));
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, CHARLIE);
+    });
+}
+
+fn buy_in_escrow() {
+    assert_ok!(create_default_collection());
+    assert_ok!(mint_default_token());
+    assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, NATIVE));
+    assert_ok!(Chiba::set_listing_escrow(Origin::signed(ALICE), 0, 0, true));
+    assert_ok!(Chiba::buy_now(Origin::signed(BOB), 0, 0));
+}
+
+#[test]
+fn buy_now_in_escrow() {
+    new_test_ext().execute_with(|| {
+        buy_in_escrow();
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(Chiba::escrow_account()), 1000);
+        assert_eq!(Balances::free_balance(ALICE), 1 << 60);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, true);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::EscrowOpened(0, 0, 11)),
+        );
+        assert_noop!(
+            Chiba::transfer(Origin::signed(BOB), 0, 0, CHARLIE),
+            crate::Error::<Test>::TokenFrozen
+        );
+    });
+}
+
+#[test]
+fn escrow_released_after_period() {
+    new_test_ext().execute_with(|| {
+        buy_in_escrow();
+
+        run_to_block(11);
+
+        assert_eq!(Chiba::escrow(0, 0), None);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 1000);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::EscrowReleased(0, 0, ALICE, 1000)),
+        );
+    });
+}
+
+#[test]
+fn escrow_release_pays_marketplace_fee() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Chiba::set_marketplace_fee(Origin::root(), Perbill::from_percent(5)));
+        buy_in_escrow();
+
+        run_to_block(11);
+
+        assert_eq!(Balances::free_balance(TREASURY), 50);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 950);
+        assert_eq!(Balances::free_balance(Chiba::escrow_account()), 0);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::EscrowReleased(0, 0, ALICE, 1000)),
+        );
+    });
+}
+
+#[test]
+fn escrow_below_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 0, NATIVE));
+
+        assert_noop!(
+            Chiba::set_listing_escrow(Origin::signed(ALICE), 0, 0, true),
+            crate::Error::<Test>::EscrowTooSmall,
+        );
+
+        // lowering the price after escrow was turned on is caught at purchase
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, NATIVE));
+        assert_ok!(Chiba::set_listing_escrow(Origin::signed(ALICE), 0, 0, true));
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 0, NATIVE));
+        assert_noop!(Chiba::buy_now(Origin::signed(BOB), 0, 0), crate::Error::<Test>::EscrowTooSmall);
+    });
+}
+
+#[test]
+fn open_dispute() {
+    new_test_ext().execute_with(|| {
+        buy_in_escrow();
+
+        assert_noop!(
+            Chiba::open_dispute(Origin::signed(CHARLIE), 0, 0),
+            crate::Error::<Test>::NotEscrowBuyer
+        );
+        assert_ok!(Chiba::open_dispute(Origin::signed(BOB), 0, 0));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::EscrowDisputed(0, 0, BOB)),
+        );
+
+        run_to_block(11);
+
+        assert!(Chiba::escrow(0, 0).unwrap().disputed);
+        assert_eq!(Balances::free_balance(ALICE), 1 << 60);
+    });
+}
+
+#[test]
+fn open_dispute_after_window() {
+    new_test_ext().execute_with(|| {
+        buy_in_escrow();
+        run_to_block(11);
+
+        assert_noop!(
+            Chiba::open_dispute(Origin::signed(BOB), 0, 0),
+            crate::Error::<Test>::EscrowNotFound
+        );
+    });
+}
+
+#[test]
+fn resolve_dispute_reverses_sale() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Chiba::set_curator(Origin::root(), CURATOR));
+        buy_in_escrow();
+        assert_ok!(Chiba::open_dispute(Origin::signed(BOB), 0, 0));
+
+        assert_noop!(
+            Chiba::resolve_dispute(Origin::signed(ALICE), 0, 0, true),
+            crate::Error::<Test>::NotCurator
+        );
+        assert_ok!(Chiba::resolve_dispute(Origin::signed(CURATOR), 0, 0, true));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, ALICE);
+        assert_eq!(Balances::free_balance(BOB), 1 << 60);
+        assert_eq!(Chiba::escrow(0, 0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::SaleReversed(0, 0, BOB, 1000)),
+        );
+    });
+}
+
+#[test]
+fn resolve_dispute_releases_payment() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Chiba::set_curator(Origin::root(), CURATOR));
+        buy_in_escrow();
+        assert_ok!(Chiba::open_dispute(Origin::signed(BOB), 0, 0));
+
+        assert_ok!(Chiba::resolve_dispute(Origin::signed(CURATOR), 0, 0, false));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 1000);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::EscrowReleased(0, 0, ALICE, 1000)),
+        );
+    });
+}
+
+#[test]
+fn create_installment_plan() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::create_installment_plan(
+            Origin::signed(ALICE), 0, 0, BOB, 1000, 100, 3, 10, Perbill::from_percent(50)
+        ));
+
+        assert_eq!(Chiba::installment_plan(0, 0).unwrap().next_due, None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::InstallmentPlanCreated(0, 0, BOB, 1000)),
+        );
+    });
+}
+
+#[test]
+fn create_installment_plan_invalid() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::create_installment_plan(Origin::signed(ALICE), 0, 0, BOB, 1000, 1000, 3, 10, Perbill::from_percent(0)),
+            crate::Error::<Test>::InvalidPlan
+        );
+        assert_noop!(
+            Chiba::create_installment_plan(Origin::signed(ALICE), 0, 0, BOB, 1000, 100, 5, 10, Perbill::from_percent(0)),
+            crate::Error::<Test>::InvalidPlan
+        );
+        assert_noop!(
+            Chiba::create_installment_plan(Origin::signed(BOB), 0, 0, CHARLIE, 1000, 100, 3, 10, Perbill::from_percent(0)),
+            crate::Error::<Test>::NotTokenOwner
+        );
+    });
+}
+
+#[test]
+fn unstarted_plan_dropped_on_transfer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_installment_plan(
+            Origin::signed(ALICE), 0, 0, CHARLIE, 1000, 100, 3, 10, Perbill::from_percent(50)
+        ));
+
+        assert_ok!(Chiba::transfer(Origin::signed(ALICE), 0, 0, BOB));
+
+        assert_eq!(Chiba::installment_plan(0, 0), None);
+        assert_noop!(
+            Chiba::start_installment_plan(Origin::signed(CHARLIE), 0, 0),
+            crate::Error::<Test>::PlanNotFound
+        );
+        assert_ok!(Chiba::create_installment_plan(
+            Origin::signed(BOB), 0, 0, CHARLIE, 2000, 100, 3, 10, Perbill::from_percent(50)
+        ));
+        assert_eq!(Chiba::installment_plan(0, 0).unwrap().seller, BOB);
+
+        assert_ok!(Chiba::burn(Origin::signed(ALICE), 0, 0));
+        assert_eq!(Chiba::installment_plan(0, 0), None);
+    });
+}
+
+#[test]
+fn installment_plan_completes() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_installment_plan(
+            Origin::signed(ALICE), 0, 0, BOB, 1000, 100, 2, 10, Perbill::from_percent(50)
+        ));
+
+        assert_ok!(Chiba::start_installment_plan(Origin::signed(BOB), 0, 0));
+        assert_eq!(Balances::reserved_balance(BOB), 100);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, true);
+        assert_noop!(
+            Chiba::transfer(Origin::signed(ALICE), 0, 0, CHARLIE),
+            crate::Error::<Test>::TokenFrozen
+        );
+
+        assert_ok!(Chiba::pay_installment(Origin::signed(BOB), 0, 0));
+        assert_eq!(Balances::reserved_balance(BOB), 550);
+        assert_eq!(Chiba::installment_plan(0, 0).unwrap().next_due, Some(21));
+
+        run_to_block(15);
+        assert_ok!(Chiba::pay_installment(Origin::signed(BOB), 0, 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 1000);
+        assert_eq!(Chiba::installment_plan(0, 0), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::InstallmentPlanCompleted(0, 0, BOB, 1000)),
+        );
+    });
+}
+
+#[test]
+fn installment_plan_defaults() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_installment_plan(
+            Origin::signed(ALICE), 0, 0, BOB, 1000, 100, 2, 10, Perbill::from_percent(50)
+        ));
+        assert_ok!(Chiba::start_installment_plan(Origin::signed(BOB), 0, 0));
+        assert_ok!(Chiba::pay_installment(Origin::signed(BOB), 0, 0));
+
+        run_to_block(21);
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, ALICE);
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 275);
+        assert_eq!(Balances::free_balance(BOB), (1 << 60) - 275);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::InstallmentPlanDefaulted(0, 0, BOB, 275)),
+        );
+    });
+}
+
+#[test]
+fn cancel_installment_plan() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_installment_plan(
+            Origin::signed(ALICE), 0, 0, BOB, 1000, 100, 2, 10, Perbill::from_percent(0)
+        ));
+
+        assert_ok!(Chiba::cancel_installment_plan(Origin::signed(ALICE), 0, 0));
+        assert_eq!(Chiba::installment_plan(0, 0), None);
+        assert_noop!(
+            Chiba::start_installment_plan(Origin::signed(BOB), 0, 0),
+            crate::Error::<Test>::PlanNotFound
+        );
+    });
+}
+
+#[test]
+fn rent_out() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::rent_out(Origin::signed(ALICE), 0, 0, 10, 20));
+
+        assert_eq!(Chiba::rental_terms(0, 0), Some(crate::RentalTerms { price_per_block: 10, max_duration: 20 }));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::RentalTermsSet(0, 0, 10, 20)),
+        );
+        assert_noop!(
+            Chiba::rent_out(Origin::signed(BOB), 0, 0, 10, 20),
+            crate::Error::<Test>::NotTokenOwner
+        );
+    });
+}
+
+#[test]
+fn rent() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::rent_out(Origin::signed(ALICE), 0, 0, 10, 20));
+
+        assert_noop!(
+            Chiba::rent(Origin::signed(BOB), 0, 0, 21),
+            crate::Error::<Test>::InvalidRentalDuration
+        );
+        assert_ok!(Chiba::rent(Origin::signed(BOB), 0, 0, 5));
+
+        assert_eq!(Chiba::token_user(0, 0), Some((BOB, 6)));
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 50);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenRented(0, 0, BOB, 6)),
+        );
+        assert_noop!(
+            Chiba::rent(Origin::signed(CHARLIE), 0, 0, 5),
+            crate::Error::<Test>::AlreadyRented
+        );
+        assert_noop!(
+            Chiba::transfer(Origin::signed(ALICE), 0, 0, CHARLIE),
+            crate::Error::<Test>::TokenFrozen
+        );
+    });
+}
+
+#[test]
+fn rental_toggle_display_by_user() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::rent_out(Origin::signed(ALICE), 0, 0, 10, 20));
+        assert_ok!(Chiba::rent(Origin::signed(BOB), 0, 0, 5));
+
+        assert_noop!(
+            Chiba::toggle_display(Origin::signed(ALICE), 0, 0, true),
+            crate::Error::<Test>::NotTokenUser
+        );
+        assert_ok!(Chiba::toggle_display(Origin::signed(BOB), 0, 0, true));
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().display_flag, true);
+
+        assert_ok!(Chiba::toggle_marketplace_listing(Origin::signed(ALICE), 0, 0, false, false));
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().display_flag, true);
+    });
+}
+
+#[test]
+fn rental_ends() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::rent_out(Origin::signed(ALICE), 0, 0, 10, 20));
+        assert_ok!(Chiba::rent(Origin::signed(BOB), 0, 0, 5));
+
+        run_to_block(6);
+
+        assert_eq!(Chiba::token_user(0, 0), None);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::RentalEnded(0, 0, BOB)),
+        );
+        assert_ok!(Chiba::toggle_display(Origin::signed(ALICE), 0, 0, true));
+        assert_ok!(Chiba::transfer(Origin::signed(ALICE), 0, 0, CHARLIE));
+        assert_eq!(Chiba::rental_terms(0, 0), None);
+    });
+}
+
+#[test]
+fn approve() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::approve(Origin::signed(ALICE), 0, 0, BOB, Some(10)));
+
+        assert_eq!(Chiba::approval(0, 0), Some((BOB, Some(10))));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::Approved(0, 0, BOB, Some(10))),
+        );
+        assert_noop!(
+            Chiba::approve(Origin::signed(BOB), 0, 0, CHARLIE, None),
+            crate::Error::<Test>::NotTokenOwner
+        );
+    });
+}
+
+#[test]
+fn approved_operator_transfers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::approve(Origin::signed(ALICE), 0, 0, BOB, None));
+
+        assert_ok!(Chiba::transfer(Origin::signed(BOB), 0, 0, CHARLIE));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, CHARLIE);
+        assert_eq!(Chiba::approval(0, 0), None);
+        assert_noop!(
+            Chiba::transfer(Origin::signed(BOB), 0, 0, ALICE),
+            crate::Error::<Test>::NotTokenOwner
+        );
+    });
+}
+
+#[test]
+fn approval_expires() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::approve(Origin::signed(ALICE), 0, 0, BOB, Some(5)));
+
+        run_to_block(5);
+
+        assert_noop!(
+            Chiba::toggle_display(Origin::signed(BOB), 0, 0, true),
+            crate::Error::<Test>::NotTokenOwner
+        );
+    });
+}
+
+#[test]
+fn operator_lists_and_accepts_offers() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_approval_for_all(Origin::signed(ALICE), 0, CURATOR, true, None));
+
+        assert_ok!(Chiba::list_token(Origin::signed(CURATOR), 0, 0, 1000, NATIVE));
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 500, NATIVE, None));
+        assert_ok!(Chiba::accept_offer(Origin::signed(CURATOR), 0, 0, BOB));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(ALICE), (1 << 60) + 500);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::OfferAccepted(0, 0, ALICE, BOB)),
+        );
+        // the operator was approved by Alice, not by the new owner
+        assert_noop!(
+            Chiba::list_token(Origin::signed(CURATOR), 0, 0, 1000, NATIVE),
+            crate::Error::<Test>::NotTokenOwner
+        );
+    });
+}
+
+#[test]
+fn revoke_approval_for_all() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_approval_for_all(Origin::signed(ALICE), 0, BOB, true, None));
+
+        assert_ok!(Chiba::set_approval_for_all(Origin::signed(ALICE), 0, BOB, false, None));
+
+        assert_eq!(Chiba::collection_operator((0, ALICE), BOB), None);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::ApprovalForAllSet(0, ALICE, BOB, false)),
+        );
+        assert_noop!(
+            Chiba::transfer(Origin::signed(BOB), 0, 0, CHARLIE),
+            crate::Error::<Test>::NotTokenOwner
+        );
+    });
+}
+
+fn mint_soulbound_token(holder_burnable: bool) {
+    assert_ok!(Chiba::create_collection(
+        Origin::signed(ALICE),
+        vec![],
+        crate::ClassData { name: vec![], soulbound: true, holder_burnable },
+        None,
+    ));
+    assert_ok!(Chiba::mint_to(Origin::signed(ALICE), 0, BOB, vec![], Default::default()));
+}
+
+#[test]
+fn mint_to() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+
+        assert_noop!(
+            Chiba::mint_to(Origin::signed(BOB), 0, BOB, vec![], Default::default()),
+            crate::Error::<Test>::NotCollectionOwner
+        );
+        assert_ok!(Chiba::mint_to(Origin::signed(ALICE), 0, BOB, vec![], Default::default()));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenMinted(0, 0)),
+        );
+    });
+}
+
+#[test]
+fn soulbound_token_cannot_move() {
+    new_test_ext().execute_with(|| {
+        mint_soulbound_token(false);
+
+        assert_noop!(
+            Chiba::transfer(Origin::signed(BOB), 0, 0, CHARLIE),
+            crate::Error::<Test>::Soulbound
+        );
+        assert_noop!(
+            Chiba::list_token(Origin::signed(BOB), 0, 0, 1000, NATIVE),
+            crate::Error::<Test>::Soulbound
+        );
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(CHARLIE), 0, 0, 1000, NATIVE, None),
+            crate::Error::<Test>::Soulbound
+        );
+        assert_noop!(
+            Chiba::create_auction(Origin::signed(BOB), 0, 0, 100, 10, 20),
+            crate::Error::<Test>::Soulbound
+        );
+    });
+}
+
+#[test]
+fn soulbound_token_cannot_be_swapped() {
+    new_test_ext().execute_with(|| {
+        use pallet_atomic_swap::SwapAction;
+        mint_soulbound_token(false);
+        let action = crate::ChibaSwapAction::<Test> { collection_id: 0, token_id: 0 };
+
+        assert_noop!(action.reserve(&BOB), crate::Error::<Test>::Soulbound);
+        assert!(!action.claim(&BOB, &CHARLIE));
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+    });
+}
+
+#[test]
+fn soulbound_holder_burn() {
+    new_test_ext().execute_with(|| {
+        mint_soulbound_token(false);
+        assert_noop!(
+            Chiba::burn(Origin::signed(BOB), 0, 0),
+            crate::Error::<Test>::NotCollectionOwnerOrCurator
+        );
+    });
+    new_test_ext().execute_with(|| {
+        mint_soulbound_token(true);
+
+        assert_ok!(Chiba::burn(Origin::signed(BOB), 0, 0));
+
+        assert!(Nft::tokens(0, 0).is_none());
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenBurned(0, 0)),
+        );
+    });
+}
+
+#[test]
+fn set_collection_settings() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        let settings = crate::CollectionSettings(crate::CollectionSettings::ALL & !crate::CollectionSettings::OFFERS_ENABLED);
+
+        assert_noop!(
+            Chiba::set_collection_settings(Origin::signed(BOB), 0, settings),
+            crate::Error::<Test>::NotCollectionOwner
+        );
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, settings));
+
+        assert_eq!(Chiba::collection_settings(0), settings);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::CollectionSettingsSet(0, settings)),
+        );
+    });
+}
+
+#[test]
+fn transfers_stay_enabled_during_sales() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_auction(Origin::signed(ALICE), 0, 0, 100, 10, 20));
+        assert_ok!(Chiba::bid(Origin::signed(BOB), 0, 0, 100));
+        let locked = crate::CollectionSettings(crate::CollectionSettings::ALL & !crate::CollectionSettings::TRANSFERABLE);
+
+        assert_eq!(Chiba::pending_sales(0), 1);
+        assert_noop!(
+            Chiba::set_collection_settings(Origin::signed(ALICE), 0, locked),
+            crate::Error::<Test>::SalesPending
+        );
+
+        run_to_block(20);
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Chiba::pending_sales(0), 0);
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, locked));
+    });
+}
+
+#[test]
+fn transfers_stay_enabled_during_swaps() {
+    new_test_ext().execute_with(|| {
+        use pallet_atomic_swap::SwapAction;
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        let action = crate::ChibaSwapAction::<Test> { collection_id: 0, token_id: 0 };
+        let locked = crate::CollectionSettings(crate::CollectionSettings::ALL & !crate::CollectionSettings::TRANSFERABLE);
+
+        assert_ok!(action.reserve(&ALICE));
+        assert_eq!(Chiba::pending_sales(0), 1);
+        assert_noop!(
+            Chiba::set_collection_settings(Origin::signed(ALICE), 0, locked),
+            crate::Error::<Test>::SalesPending
+        );
+
+        assert!(action.claim(&ALICE, &BOB));
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Chiba::token_extended_info(0, 0).unwrap().frozen, false);
+        assert_eq!(Chiba::pending_sales(0), 0);
+
+        assert_ok!(action.reserve(&BOB));
+        action.cancel(&BOB);
+        assert_eq!(Chiba::pending_sales(0), 0);
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, locked));
+    });
+}
+
+#[test]
+fn collection_settings_cannot_loosen() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        let locked = crate::CollectionSettings(crate::CollectionSettings::BURNABLE);
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, locked));
+
+        assert_noop!(
+            Chiba::set_collection_settings(Origin::signed(ALICE), 0, crate::CollectionSettings::default()),
+            crate::Error::<Test>::InvalidSettings
+        );
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, crate::CollectionSettings(0)));
+    });
+}
+
+#[test]
+fn collection_settings_are_enforced() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, crate::CollectionSettings(0)));
+
+        assert_noop!(
+            Chiba::transfer(Origin::signed(ALICE), 0, 0, BOB),
+            crate::Error::<Test>::TransfersDisabled
+        );
+        assert_noop!(
+            Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, NATIVE),
+            crate::Error::<Test>::TransfersDisabled
+        );
+        assert_noop!(
+            Chiba::burn(Origin::signed(ALICE), 0, 0),
+            crate::Error::<Test>::BurningDisabled
+        );
+        assert_noop!(
+            Chiba::update_token_metadata(Origin::signed(ALICE), 0, 0, vec![1]),
+            crate::Error::<Test>::MetadataLocked
+        );
+    });
+}
+
+#[test]
+fn offers_disabled() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        let settings = crate::CollectionSettings(crate::CollectionSettings::ALL & !crate::CollectionSettings::OFFERS_ENABLED);
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, settings));
+
+        assert_noop!(
+            Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None),
+            crate::Error::<Test>::OffersDisabled
+        );
+        assert_noop!(
+            Chiba::create_collection_offer(Origin::signed(BOB), 0, 100, NATIVE, 1),
+            crate::Error::<Test>::OffersDisabled
+        );
+        assert_ok!(Chiba::list_token(Origin::signed(ALICE), 0, 0, 1000, NATIVE));
+    });
+}
+
+#[test]
+fn offers_disabled_after_offer_made() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+        assert_ok!(Chiba::create_offer(Origin::signed(BOB), 0, 0, 100, NATIVE, None));
+        let settings = crate::CollectionSettings(crate::CollectionSettings::ALL & !crate::CollectionSettings::OFFERS_ENABLED);
+        assert_ok!(Chiba::set_collection_settings(Origin::signed(ALICE), 0, settings));
+
+        assert_noop!(
+            Chiba::counter_offer(Origin::signed(ALICE), 0, 0, BOB, 200),
+            crate::Error::<Test>::OffersDisabled
+        );
+        assert_noop!(
+            Chiba::accept_offer(Origin::signed(ALICE), 0, 0, BOB),
+            crate::Error::<Test>::OffersDisabled
+        );
+        assert_ok!(Chiba::cancel_offer(Origin::signed(BOB), 0, 0));
+        assert_eq!(Balances::reserved_balance(BOB), 0);
+    });
+}
+
+#[test]
+fn update_token_metadata() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(mint_default_token());
+
+        assert_ok!(Chiba::update_token_metadata(Origin::signed(ALICE), 0, 0, vec![1, 2, 3]));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().metadata, vec![1, 2, 3]);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::TokenMetadataUpdated(0, 0)),
+        );
+        assert_noop!(
+            Chiba::update_token_metadata(Origin::signed(BOB), 0, 0, vec![]),
+            crate::Error::<Test>::NotCollectionOwner
+        );
+    });
+}
+
+#[test]
+fn mint_max_supply_reached() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Chiba::create_collection(Origin::signed(ALICE), vec![], Default::default(), Some(2)));
+        assert_eq!(Chiba::max_supply(0), Some(2));
+
+        assert_ok!(Chiba::mint(Origin::signed(ALICE), 0, vec![], Default::default()));
+        assert_ok!(Chiba::mint_to(Origin::signed(ALICE), 0, BOB, vec![], Default::default()));
+
+        assert_noop!(
+            Chiba::mint(Origin::signed(ALICE), 0, vec![], Default::default()),
+            crate::Error::<Test>::MaxSupplyReached
+        );
+        // burned tokens still count towards the edition size
+        assert_ok!(Chiba::burn(Origin::signed(ALICE), 0, 0));
+        assert_noop!(
+            Chiba::mint(Origin::signed(ALICE), 0, vec![], Default::default()),
+            crate::Error::<Test>::MaxSupplyReached
+        );
+    });
+}
+
+#[test]
+fn set_max_supply() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(mint_default_token());
+        assert_ok!(mint_default_token());
+
+        assert_noop!(
+            Chiba::set_max_supply(Origin::signed(BOB), 0, 5),
+            crate::Error::<Test>::NotCollectionOwner
+        );
+        assert_noop!(
+            Chiba::set_max_supply(Origin::signed(ALICE), 0, 1),
+            crate::Error::<Test>::InvalidMaxSupply
+        );
+        assert_ok!(Chiba::set_max_supply(Origin::signed(ALICE), 0, 2));
+
+        assert_eq!(Chiba::max_supply(0), Some(2));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::MaxSupplySet(0, 2)),
+        );
+        assert_noop!(
+            Chiba::set_max_supply(Origin::signed(ALICE), 0, 3),
+            crate::Error::<Test>::MaxSupplyAlreadySet
+        );
+        assert_noop!(
+            Chiba::mint(Origin::signed(ALICE), 0, vec![], Default::default()),
+            crate::Error::<Test>::MaxSupplyReached
+        );
+    });
+}
+
+fn mint_config() -> crate::MintConfig<u64, u64, u64> {
+    crate::MintConfig {
+        price: 100,
+        beneficiary: CHARLIE,
+        allowlist_start: 5,
+        public_start: 10,
+        end: 20,
+        per_account_limit: 2,
+    }
+}
+
+#[test]
+fn set_mint_config() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+
+        assert_noop!(
+            Chiba::set_mint_config(Origin::signed(BOB), 0, Some(mint_config())),
+            crate::Error::<Test>::NotCollectionOwner
+        );
+        assert_noop!(
+            Chiba::set_mint_config(Origin::signed(ALICE), 0, Some(crate::MintConfig { end: 10, ..mint_config() })),
+            crate::Error::<Test>::InvalidMintWindow
+        );
+        assert_ok!(Chiba::set_mint_config(Origin::signed(ALICE), 0, Some(mint_config())));
+
+        assert_eq!(Chiba::mint_config(0), Some(mint_config()));
+        assert_eq!(Chiba::mint_phase_changes(10, 0), Some(crate::MintPhase::Public));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::MintConfigSet(0)),
+        );
+
+        assert_ok!(Chiba::set_mint_config(Origin::signed(ALICE), 0, None));
+        assert_eq!(Chiba::mint_config(0), None);
+        assert_eq!(Chiba::mint_phase_changes(10, 0), None);
+    });
+}
+
+#[test]
+fn mint_phases_emit_events() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(Chiba::set_mint_config(Origin::signed(ALICE), 0, Some(mint_config())));
+
+        run_to_block(5);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::MintPhaseStarted(0, crate::MintPhase::Allowlist)),
+        );
+        run_to_block(10);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::MintPhaseStarted(0, crate::MintPhase::Public)),
+        );
+        run_to_block(20);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::MintPhaseStarted(0, crate::MintPhase::Closed)),
+        );
+    });
+}
+
+#[test]
+fn public_mint_allowlist_phase() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        assert_ok!(Chiba::set_mint_config(Origin::signed(ALICE), 0, Some(mint_config())));
+        assert_ok!(Chiba::update_mint_allowlist(Origin::signed(ALICE), 0, vec![BOB], true));
+
+        assert_noop!(
+            Chiba::public_mint(Origin::signed(BOB), 0),
+            crate::Error::<Test>::MintNotOpen
+        );
+        run_to_block(5);
+        assert_noop!(
+            Chiba::public_mint(Origin::signed(ALICE), 0),
+            crate::Error::<Test>::NotOnAllowlist
+        );
+        assert_ok!(Chiba::public_mint(Origin::signed(BOB), 0));
+
+        assert_eq!(Nft::tokens(0, 0).unwrap().owner, BOB);
+        assert_eq!(Balances::free_balance(CHARLIE), (1 << 60) + 100);
+        assert_eq!(Chiba::minted_by(0, BOB), 1);
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::PublicMint(0, 0, BOB, 100)),
+        );
+    });
+}
+
+#[test]
+fn public_mint_limits() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Chiba::create_collection(Origin::signed(ALICE), vec![], Default::default(), Some(3)));
+        assert_ok!(Chiba::set_mint_config(Origin::signed(ALICE), 0, Some(mint_config())));
+        run_to_block(10);
+
+        assert_ok!(Chiba::public_mint(Origin::signed(BOB), 0));
+        assert_ok!(Chiba::public_mint(Origin::signed(BOB), 0));
+        assert_noop!(
+            Chiba::public_mint(Origin::signed(BOB), 0),
+            crate::Error::<Test>::MintLimitReached
+        );
+        assert_ok!(Chiba::public_mint(Origin::signed(ALICE), 0));
+        assert_noop!(
+            Chiba::public_mint(Origin::signed(CHARLIE), 0),
+            crate::Error::<Test>::MaxSupplyReached
+        );
+
+        run_to_block(20);
+        assert_noop!(
+            Chiba::public_mint(Origin::signed(CHARLIE), 0),
+            crate::Error::<Test>::MintClosed
+        );
+    });
+}
+
+fn claim_leaf(account: u64, quantity: u32) -> [u8; 32] {
+    sp_io::hashing::blake2_256(&codec::Encode::encode(&(account, quantity)))
+}
+
+#[test]
+fn claim_mint_with_proof() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(create_default_collection());
+        let (bob, charlie) = (claim_leaf(BOB, 2), claim_leaf(CHARLIE, 1));
+        let root = Chiba::merkle_root(bob, &[charlie]);
+
+        assert_noop!(
+            Chiba::set_claim_root(Origin::signed(BOB), 0, Some(root)),
+            crate::Error::<Test>::NotCollectionOwner
+        );
+        assert_noop!(
+            Chiba::claim_mint(Origin::signed(BOB), 0, 2, vec![charlie]),
+            crate::Error::<Test>::MintNotOpen
+        );
+        assert_ok!(Chiba::set_claim_root(Origin::signed(ALICE), 0, Some(root)));
+
+        assert_ok!(Chiba::claim_mint(Origin::signed(BOB), 0, 2, vec![charlie]));
+        assert_eq!(
+            last_event(),
+            crate::mock::Event::chiba(crate::RawEvent::MintClaimed(0, 0, BOB)),
+        );
+        assert_ok!(Chiba::claim_mint(Origin::signed(BOB), 0, 2, vec![charlie]));
+        assert_noop!(
+            Chiba::claim_mint(Origin::signed(BOB), 0, 2, vec![charlie]),
+            crate::Error::<Test>::MintLimitReached
+        );
+        assert_eq!(Chiba::claimed(0, BOB), 2);
+
+        assert_noop!(
+            Chiba::claim_mint(Origin::signed(CHARLIE), 0, 2, vec![bob]),
+            crate::Error::<Test>::InvalidProof
+        );
+        assert_noop!(
+            Chiba::claim_mint(Origin::signed(ALICE), 0, 1, vec![bob]),
+            crate::Error::<Test>::InvalidProof
+        );
+        assert_ok!(Chiba::claim_mint(Origin::signed(CHARLIE), 0, 1, vec![bob]));
+        assert_eq!(Nft::tokens(0, 2).unwrap().owner, CHARLIE);
+    });
+}